@@ -44,6 +44,10 @@ impl ProcessExt for Process {
         0
     }
 
+    fn memory_bytes(&self) -> u64 {
+        0
+    }
+
     fn virtual_memory(&self) -> u64 {
         0
     }
@@ -71,4 +75,16 @@ impl ProcessExt for Process {
     fn disk_usage(&self) -> DiskUsage {
         DiskUsage::default()
     }
+
+    fn thread_count(&self) -> usize {
+        0
+    }
+
+    fn page_faults(&self) -> u64 {
+        0
+    }
+
+    fn page_faults_delta(&self) -> u64 {
+        0
+    }
 }
@@ -46,6 +46,9 @@ pub struct Process {
     pub(crate) old_written_bytes: u64,
     pub(crate) read_bytes: u64,
     pub(crate) written_bytes: u64,
+    thread_count: usize,
+    page_faults: u32,
+    old_page_faults: u32,
 }
 
 impl Process {
@@ -75,6 +78,9 @@ impl Process {
             old_written_bytes: 0,
             read_bytes: 0,
             written_bytes: 0,
+            thread_count: 0,
+            page_faults: 0,
+            old_page_faults: 0,
         }
     }
 
@@ -116,6 +122,9 @@ impl Process {
             old_written_bytes: 0,
             read_bytes: 0,
             written_bytes: 0,
+            thread_count: 0,
+            page_faults: 0,
+            old_page_faults: 0,
         }
     }
 
@@ -145,6 +154,9 @@ impl Process {
             old_written_bytes: 0,
             read_bytes: 0,
             written_bytes: 0,
+            thread_count: 0,
+            page_faults: 0,
+            old_page_faults: 0,
         }
     }
 }
@@ -184,6 +196,10 @@ impl ProcessExt for Process {
     }
 
     fn memory(&self) -> u64 {
+        self.memory / 1_000
+    }
+
+    fn memory_bytes(&self) -> u64 {
         self.memory
     }
 
@@ -213,12 +229,25 @@ impl ProcessExt for Process {
 
     fn disk_usage(&self) -> DiskUsage {
         DiskUsage {
-            read_bytes: self.read_bytes - self.old_read_bytes,
+            read_bytes: self.read_bytes.saturating_sub(self.old_read_bytes),
             total_read_bytes: self.read_bytes,
-            written_bytes: self.written_bytes - self.old_written_bytes,
+            written_bytes: self.written_bytes.saturating_sub(self.old_written_bytes),
             total_written_bytes: self.written_bytes,
+            ..DiskUsage::default()
         }
     }
+
+    fn thread_count(&self) -> usize {
+        self.thread_count
+    }
+
+    fn page_faults(&self) -> u64 {
+        self.page_faults as u64
+    }
+
+    fn page_faults_delta(&self) -> u64 {
+        self.page_faults.wrapping_sub(self.old_page_faults) as u64
+    }
 }
 
 #[allow(deprecated)] // Because of libc::mach_absolute_time.
@@ -342,8 +371,13 @@ pub(crate) fn update_process(
                 compute_cpu_usage(p, task_info, system_time, user_time, time_interval);
             }
 
-            p.memory = task_info.pti_resident_size / 1_000;
+            p.memory = task_info.pti_resident_size;
             p.virtual_memory = task_info.pti_virtual_size / 1_000;
+            if refresh_kind.threads() {
+                p.thread_count = task_info.pti_threadnum as usize;
+            }
+            p.old_page_faults = p.page_faults;
+            p.page_faults = task_info.pti_faults as u32;
             if refresh_kind.disk_usage() {
                 update_proc_disk_activity(p);
             }
@@ -548,8 +582,13 @@ pub(crate) fn update_process(
 
         let task_info = get_task_info(pid);
 
-        p.memory = task_info.pti_resident_size / 1_000;
+        p.memory = task_info.pti_resident_size;
         p.virtual_memory = task_info.pti_virtual_size / 1_000;
+        if refresh_kind.threads() {
+            p.thread_count = task_info.pti_threadnum as usize;
+        }
+        p.page_faults = task_info.pti_faults as u32;
+        p.old_page_faults = p.page_faults;
 
         p.uid = info.pbi_uid;
         p.gid = info.pbi_gid;
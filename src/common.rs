@@ -4,7 +4,9 @@ use crate::{NetworkData, Networks, NetworksExt, UserExt};
 
 use std::convert::From;
 use std::fmt;
+use std::path::PathBuf;
 use std::str::FromStr;
+use std::time::Duration;
 
 /// Trait to have a common conversions for the [`Pid`][crate::Pid] type.
 ///
@@ -137,7 +139,7 @@ assert_eq!(r.", stringify!($name), "(), true);
 ```
 use sysinfo::", stringify!($ty_name), ";
 
-let r = ", stringify!($ty_name), "::everything();
+let r = ", stringify!($ty_name), "::new().with_", stringify!($name), "();
 assert_eq!(r.", stringify!($name), "(), true);
 
 let r = r.without_", stringify!($name), "();
@@ -167,11 +169,67 @@ assert_eq!(r.", stringify!($name), "(), false);
 /// }
 /// ```
 ///
+/// The `exe` refresh kind is only used on Windows, when a process is added to the list: its
+/// executable path (and its name) is always retrieved with `QueryFullProcessImageNameW`, which is
+/// cheap. If it fails and `exe` is set, the modules of the process are enumerated to find it,
+/// which is slow. Otherwise, the executable path is left empty for these processes.
+///
+/// The `user` refresh kind is only used on Windows too: the user running a process is read from
+/// its token, once per process. Opening the token of every process is slow, so it's not set by
+/// [`ProcessRefreshKind::everything`].
+///
+/// The `elevation` refresh kind is only used on Windows as well: whether a process is elevated is
+/// read from its token, once per process. Like `user`, it's not set by
+/// [`ProcessRefreshKind::everything`].
+///
+/// The `integrity_level` refresh kind is only used on Windows too: it's read from the token of
+/// the process on every refresh since it can be lowered while the process is running. It's not
+/// set by [`ProcessRefreshKind::everything`] either.
+///
+/// The `handles` refresh kind is only used on Windows too, to update the number of handles opened
+/// by a process.
+///
+/// The `session` refresh kind is only used on Windows too: the Terminal Services session of a
+/// process is read once per process.
+///
+/// The `modules` refresh kind is only used on Windows as well, to update the list of the modules
+/// (DLLs) loaded by a process. Enumerating them is slow (it's done on every refresh), which is why
+/// it's not set by [`ProcessRefreshKind::everything`]: use
+/// [`ProcessRefreshKind::with_modules`] to get them.
+///
+/// The `cmd` refresh kind is only used on Windows as well: the command line, the environment and
+/// the current working directory of a process are read from its memory when it's added to the list,
+/// and then only read again while they're empty (unless they can't be read at all, like for
+/// protected processes). Setting it forces them to be read again on every refresh, which is
+/// expensive. This is why it's not set by [`ProcessRefreshKind::everything`].
+///
+/// The `network` refresh kind is only used on Windows: the number of bytes received and sent by a
+/// process on its TCP connections is read on every refresh. The TCP connections of the system are
+/// listed once per refresh, but the statistics of each of them are read separately, so it's not
+/// set by [`ProcessRefreshKind::everything`]. See
+/// [`set_tcp_statistics_collection`][crate::set_tcp_statistics_collection] as well.
+///
+/// The `cwd` refresh kind is used on Linux and Windows: the current working directory of a process
+/// is only read when it's added to the list, unless this is set. It's cheaper than `cmd` on
+/// Windows, but it still requires reading the memory of the process so it's not set by
+/// [`ProcessRefreshKind::everything`] either.
+///
 /// [`Process`]: crate::Process
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub struct ProcessRefreshKind {
     cpu: bool,
     disk_usage: bool,
+    exe: bool,
+    threads: bool,
+    user: bool,
+    elevation: bool,
+    integrity_level: bool,
+    handles: bool,
+    session: bool,
+    modules: bool,
+    cmd: bool,
+    cwd: bool,
+    network: bool,
 }
 
 impl ProcessRefreshKind {
@@ -184,12 +242,24 @@ impl ProcessRefreshKind {
     ///
     /// assert_eq!(r.cpu(), false);
     /// assert_eq!(r.disk_usage(), false);
+    /// assert_eq!(r.exe(), false);
+    /// assert_eq!(r.threads(), false);
+    /// assert_eq!(r.user(), false);
+    /// assert_eq!(r.elevation(), false);
+    /// assert_eq!(r.integrity_level(), false);
+    /// assert_eq!(r.handles(), false);
+    /// assert_eq!(r.session(), false);
+    /// assert_eq!(r.modules(), false);
+    /// assert_eq!(r.cmd(), false);
+    /// assert_eq!(r.cwd(), false);
+    /// assert_eq!(r.network(), false);
     /// ```
     pub fn new() -> Self {
         Self::default()
     }
 
-    /// Creates a new `ProcessRefreshKind` with every refresh set to `true`.
+    /// Creates a new `ProcessRefreshKind` with every refresh set to `true`, except `user`,
+    /// `elevation`, `integrity_level`, `modules`, `cmd`, `cwd` and `network`.
     ///
     /// ```
     /// use sysinfo::ProcessRefreshKind;
@@ -198,11 +268,33 @@ impl ProcessRefreshKind {
     ///
     /// assert_eq!(r.cpu(), true);
     /// assert_eq!(r.disk_usage(), true);
+    /// assert_eq!(r.exe(), true);
+    /// assert_eq!(r.threads(), true);
+    /// assert_eq!(r.user(), false);
+    /// assert_eq!(r.elevation(), false);
+    /// assert_eq!(r.integrity_level(), false);
+    /// assert_eq!(r.handles(), true);
+    /// assert_eq!(r.session(), true);
+    /// assert_eq!(r.modules(), false);
+    /// assert_eq!(r.cmd(), false);
+    /// assert_eq!(r.cwd(), false);
+    /// assert_eq!(r.network(), false);
     /// ```
     pub fn everything() -> Self {
         Self {
             cpu: true,
             disk_usage: true,
+            exe: true,
+            threads: true,
+            user: false,
+            elevation: false,
+            integrity_level: false,
+            handles: true,
+            session: true,
+            modules: false,
+            cmd: false,
+            cwd: false,
+            network: false,
         }
     }
 
@@ -213,6 +305,27 @@ impl ProcessRefreshKind {
         with_disk_usage,
         without_disk_usage
     );
+    impl_get_set!(ProcessRefreshKind, exe, with_exe, without_exe);
+    impl_get_set!(ProcessRefreshKind, threads, with_threads, without_threads);
+    impl_get_set!(ProcessRefreshKind, user, with_user, without_user);
+    impl_get_set!(
+        ProcessRefreshKind,
+        elevation,
+        with_elevation,
+        without_elevation
+    );
+    impl_get_set!(
+        ProcessRefreshKind,
+        integrity_level,
+        with_integrity_level,
+        without_integrity_level
+    );
+    impl_get_set!(ProcessRefreshKind, handles, with_handles, without_handles);
+    impl_get_set!(ProcessRefreshKind, session, with_session, without_session);
+    impl_get_set!(ProcessRefreshKind, modules, with_modules, without_modules);
+    impl_get_set!(ProcessRefreshKind, cmd, with_cmd, without_cmd);
+    impl_get_set!(ProcessRefreshKind, cwd, with_cwd, without_cwd);
+    impl_get_set!(ProcessRefreshKind, network, with_network, without_network);
 }
 
 /// Used to determine what you want to refresh specifically on the [`System`] type.
@@ -665,6 +778,9 @@ impl UserExt for User {
 
 /// Type containing read and written bytes.
 ///
+/// The numbers of read and write operations are only available on Windows: they're always `0` on
+/// the other platforms.
+///
 /// It is returned by [`ProcessExt::disk_usage`][crate::ProcessExt::disk_usage].
 ///
 /// ```no_run
@@ -687,14 +803,35 @@ impl UserExt for User {
 /// ```
 #[derive(Debug, Default, Clone, Copy, PartialEq, PartialOrd)]
 pub struct DiskUsage {
-    /// Total number of written bytes.
+    /// Total number of written bytes since the process started. It never decreases, whatever
+    /// the refresh frequency is.
     pub total_written_bytes: u64,
     /// Number of written bytes since the last refresh.
     pub written_bytes: u64,
-    /// Total number of read bytes.
+    /// Total number of read bytes since the process started. It never decreases, whatever the
+    /// refresh frequency is.
     pub total_read_bytes: u64,
     /// Number of read bytes since the last refresh.
     pub read_bytes: u64,
+    /// Total number of write operations since the process started.
+    pub total_written_operations: u64,
+    /// Number of write operations since the last refresh.
+    pub written_operations: u64,
+    /// Total number of read operations since the process started.
+    pub total_read_operations: u64,
+    /// Number of read operations since the last refresh.
+    pub read_operations: u64,
+}
+
+/// A process and its child processes, as returned by [`SystemExt::process_forest`].
+///
+/// [`SystemExt::process_forest`]: crate::SystemExt::process_forest
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessNode {
+    /// PID of the process.
+    pub pid: Pid,
+    /// Child processes of the process, sorted by PID.
+    pub children: Vec<ProcessNode>,
 }
 
 /// Enum describing the different status of a process.
@@ -798,6 +935,210 @@ pub enum ProcessStatus {
     Unknown(u32),
 }
 
+/// Enum describing the machine architecture an executable image was built for.
+///
+/// This type is returned by `Process::machine_type` on Windows.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum MachineType {
+    /// x86 (32-bit) image.
+    X86,
+    /// x86-64 image.
+    X64,
+    /// ARM (32-bit, Thumb-2) image.
+    Arm,
+    /// ARM64 image.
+    Arm64,
+    /// Unknown machine type. Contains the raw `Machine` value of the image header.
+    Unknown(u16),
+}
+
+/// Enum describing the signing level the kernel requires for the images loaded by a process,
+/// from the least to the most trusted one.
+///
+/// This type is returned by `Process::signing_level` on Windows.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum SigningLevel {
+    /// No signature is required.
+    Unchecked,
+    /// Any valid Authenticode signature.
+    Authenticode,
+    /// Signed by the Microsoft Store.
+    Store,
+    /// Signed by an antimalware vendor.
+    Antimalware,
+    /// Signed by Microsoft.
+    Microsoft,
+    /// Code generated at runtime by a trusted code generator.
+    DynamicCodegen,
+    /// Part of Windows.
+    Windows,
+    /// Part of the Windows trusted computing base.
+    WindowsTcb,
+}
+
+/// Enum describing the integrity level of a process, from the least to the most trusted one.
+///
+/// This type is returned by `Process::integrity_level` on Windows.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum IntegrityLevel {
+    /// Untrusted level, used by anonymous processes.
+    Untrusted,
+    /// Low level, used by sandboxed processes like browser tabs.
+    Low,
+    /// Medium level, used by the processes of standard users.
+    Medium,
+    /// High level, used by elevated processes.
+    High,
+    /// System level, used by services and system processes.
+    System,
+}
+
+/// Enum describing the priority class of a process, from the lowest to the highest one.
+///
+/// This type is returned by `Process::priority` on Windows.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum Priority {
+    /// Only runs when the system is idle.
+    Idle,
+    /// Between `Idle` and `Normal`.
+    BelowNormal,
+    /// Default priority class.
+    Normal,
+    /// Between `Normal` and `High`.
+    AboveNormal,
+    /// For time-critical tasks.
+    High,
+    /// Highest priority class, preempting even the system threads.
+    Realtime,
+}
+
+/// Enum describing the state of a Windows service, as reported by the Service Control Manager.
+///
+/// This type is returned by `Process::service_states` on Windows.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ServiceState {
+    /// The service isn't running.
+    Stopped,
+    /// The service is starting.
+    StartPending,
+    /// The service is stopping.
+    StopPending,
+    /// The service is running.
+    Running,
+    /// The service is resuming after having been paused.
+    ContinuePending,
+    /// The service is pausing.
+    PausePending,
+    /// The service is paused.
+    Paused,
+    /// Unknown state. Contains the raw `dwCurrentState` value.
+    Unknown(u32),
+}
+
+/// Enum describing the architecture a process runs as.
+///
+/// This type is returned by `Process::architecture` on Windows.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ProcessArch {
+    /// 32-bit x86 process, running natively or in the WOW64 emulator.
+    X86,
+    /// x86-64 process.
+    X64,
+    /// ARM64 process.
+    Arm64,
+    /// The architecture of the system isn't one of the above.
+    Unknown,
+}
+
+/// Struct describing a module (the main image or a DLL) loaded by a process.
+///
+/// This type is returned by `Process::modules` on Windows.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ModuleInfo {
+    /// Path of the module file.
+    pub path: PathBuf,
+    /// Address the module is loaded at in the process memory.
+    pub base_address: usize,
+    /// Size of the module image in memory, in bytes.
+    pub size: usize,
+}
+
+/// Enum describing how much a process is constrained by the memory available to it.
+///
+/// This type is returned by `Process::memory_pressure` on Windows, which documents how it's
+/// computed.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum MemoryPressure {
+    /// The process doesn't seem to lack memory.
+    Low,
+    /// The process is using as much memory as it ever did or is faulting pages regularly.
+    Medium,
+    /// The process is faulting a lot of pages.
+    High,
+}
+
+/// Struct containing the number of bytes a process received and sent over the network.
+///
+/// This type is returned by `Process::network_usage` on Windows.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct NetworkUsage {
+    /// Number of bytes received since the last refresh.
+    pub rx_bytes: u64,
+    /// Total number of bytes received.
+    pub total_rx_bytes: u64,
+    /// Number of bytes sent since the last refresh.
+    pub tx_bytes: u64,
+    /// Total number of bytes sent.
+    pub total_tx_bytes: u64,
+}
+
+/// Struct containing the resources used by all the processes of a job object.
+///
+/// This type is returned by `Process::job_accounting` on Windows.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct JobAccounting {
+    /// Time spent in user mode by all the processes of the job, including the exited ones.
+    pub total_user_time: Duration,
+    /// Time spent in kernel mode by all the processes of the job, including the exited ones.
+    pub total_kernel_time: Duration,
+    /// Number of page faults of all the processes of the job.
+    pub total_page_faults: u32,
+    /// Number of processes which have been part of the job.
+    pub total_processes: u32,
+    /// Number of processes currently part of the job.
+    pub active_processes: u32,
+    /// Number of processes terminated because of a limit of the job.
+    pub total_terminated_processes: u32,
+}
+
+/// Struct describing how DLLs are looked up when loaded by a process.
+///
+/// This type is returned by `Process::dll_search_mode` on Windows.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct DllSearchMode {
+    /// Whether `SafeDllSearchMode` is enabled. It is a system-wide setting which moves the
+    /// current directory after the system directories in the search order.
+    pub safe_dll_search_mode: bool,
+    /// Whether the process prefers images from the `System32` directory over the ones in the
+    /// application directory.
+    pub prefer_system32_images: bool,
+    /// Whether the process is forbidden to load images from remote locations.
+    pub no_remote_images: bool,
+    /// Whether the process is forbidden to load images with a low mandatory label.
+    pub no_low_mandatory_label_images: bool,
+}
+
+/// Struct describing a handle opened by a process.
+///
+/// This type is returned by `Process::open_handles` on Windows.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct HandleInfo {
+    /// Type of the object targeted by the handle (`"File"`, `"Key"`, `"Event"`...).
+    pub type_name: String,
+    /// Name of the object targeted by the handle, if it has one and it could be retrieved.
+    pub name: Option<String>,
+}
+
 /// Returns the pid for the current process.
 ///
 /// `Err` is returned in case the platform isn't supported.
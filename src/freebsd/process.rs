@@ -63,6 +63,9 @@ pub struct Process {
     old_read_bytes: u64,
     written_bytes: u64,
     old_written_bytes: u64,
+    thread_count: usize,
+    page_faults: u64,
+    old_page_faults: u64,
 }
 
 impl ProcessExt for Process {
@@ -100,6 +103,10 @@ impl ProcessExt for Process {
     }
 
     fn memory(&self) -> u64 {
+        self.memory / 1_000
+    }
+
+    fn memory_bytes(&self) -> u64 {
         self.memory
     }
 
@@ -133,8 +140,21 @@ impl ProcessExt for Process {
             total_written_bytes: self.written_bytes,
             read_bytes: self.read_bytes.saturating_sub(self.old_read_bytes),
             total_read_bytes: self.read_bytes,
+            ..DiskUsage::default()
         }
     }
+
+    fn thread_count(&self) -> usize {
+        self.thread_count
+    }
+
+    fn page_faults(&self) -> u64 {
+        self.page_faults
+    }
+
+    fn page_faults_delta(&self) -> u64 {
+        self.page_faults.saturating_sub(self.old_page_faults)
+    }
 }
 
 pub(crate) unsafe fn get_process_data(
@@ -166,7 +186,13 @@ pub(crate) unsafe fn get_process_data(
 
     // from FreeBSD source /src/usr.bin/top/machine.c
     let virtual_memory = (kproc.ki_size / 1_000) as u64;
-    let memory = (kproc.ki_rssize * page_size) as u64 / 1_000;
+    let memory = (kproc.ki_rssize * page_size) as u64;
+    let thread_count = if refresh_kind.threads() {
+        kproc.ki_numthreads as usize
+    } else {
+        0
+    };
+    let page_faults = (kproc.ki_rusage.ru_minflt + kproc.ki_rusage.ru_majflt) as u64;
     // FIXME: This is to get the "real" run time (in micro-seconds).
     // let run_time = (kproc.ki_runtime + 5_000) / 10_000;
 
@@ -176,6 +202,11 @@ pub(crate) unsafe fn get_process_data(
         proc_.status = status;
         proc_.virtual_memory = virtual_memory;
         proc_.memory = memory;
+        if refresh_kind.threads() {
+            proc_.thread_count = thread_count;
+        }
+        proc_.old_page_faults = proc_.page_faults;
+        proc_.page_faults = page_faults;
         proc_.run_time = now.saturating_sub(proc_.start_time);
         proc_.updated = true;
 
@@ -243,6 +274,9 @@ pub(crate) unsafe fn get_process_data(
         old_read_bytes: 0,
         written_bytes: kproc.ki_rusage.ru_oublock as _,
         old_written_bytes: 0,
+        thread_count,
+        page_faults,
+        old_page_faults: page_faults,
         updated: true,
     })
 }
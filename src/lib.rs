@@ -59,8 +59,10 @@ cfg_if::cfg_if! {
 }
 
 pub use common::{
-    get_current_pid, DiskType, DiskUsage, Gid, LoadAvg, NetworksIter, Pid, PidExt,
-    ProcessRefreshKind, ProcessStatus, RefreshKind, Signal, Uid, User,
+    get_current_pid, DiskType, DiskUsage, DllSearchMode, Gid, HandleInfo, IntegrityLevel,
+    JobAccounting, LoadAvg, MachineType, MemoryPressure, ModuleInfo, NetworkUsage, NetworksIter,
+    Pid, PidExt, Priority, ProcessArch, ProcessNode, ProcessRefreshKind, ProcessStatus,
+    RefreshKind, ServiceState, Signal, SigningLevel, Uid, User,
 };
 pub use sys::{Component, Disk, NetworkData, Networks, Process, Processor, System};
 pub use traits::{
@@ -130,6 +132,69 @@ pub fn set_open_files_limit(mut _new_limit: isize) -> bool {
     }
 }
 
+/// This function is only used on windows targets, on the other platforms it does nothing and
+/// returns `false`.
+///
+/// On windows, the command line and the environment of a process are read from its memory. This
+/// function sets the maximum number of bytes read for each of them (4 MB by default), so that a
+/// bogus length can't trigger a huge allocation. The environment is truncated if it's bigger than
+/// this limit and the command line is ignored.
+///
+/// Returns `true` if the new value has been set.
+///
+/// ```no_run
+/// use sysinfo::{System, SystemExt, set_process_data_size_limit};
+///
+/// // We call the function before any call to the processes update.
+/// if !set_process_data_size_limit(1024 * 1024) {
+///     // It'll always return false on non-windows targets.
+///     eprintln!("failed to update the process data size limit...");
+/// }
+/// let s = System::new_all();
+/// ```
+pub fn set_process_data_size_limit(_new_limit: usize) -> bool {
+    cfg_if::cfg_if! {
+        if #[cfg(all(not(feature = "unknown-ci"), windows))] {
+            sys::process::PROCESS_DATA_SIZE_LIMIT
+                .store(_new_limit, std::sync::atomic::Ordering::Relaxed);
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// This function is only used on windows targets, on the other platforms it does nothing and
+/// returns `false`.
+///
+/// On windows, the statistics of the TCP connections (used by `Process::network_usage`) aren't
+/// collected by default. When this is set to `true`, their collection is enabled on the
+/// connections of the processes refreshed with [`ProcessRefreshKind::network`] set, which
+/// requires administrator privileges. It's disabled by default because this is a system-wide
+/// setting: it stays enabled on these connections until they're closed, even if this is set to
+/// `false` again or if the current process exits.
+///
+/// Returns `true` if the new value has been set.
+///
+/// ```no_run
+/// use sysinfo::{ProcessRefreshKind, System, SystemExt, set_tcp_statistics_collection};
+///
+/// set_tcp_statistics_collection(true);
+/// let mut s = System::new();
+/// s.refresh_processes_specifics(ProcessRefreshKind::new().with_network());
+/// ```
+pub fn set_tcp_statistics_collection(_enable: bool) -> bool {
+    cfg_if::cfg_if! {
+        if #[cfg(all(not(feature = "unknown-ci"), windows))] {
+            sys::tcp_stats::TCP_STATISTICS_COLLECTION
+                .store(_enable, std::sync::atomic::Ordering::Relaxed);
+            true
+        } else {
+            false
+        }
+    }
+}
+
 // FIXME: Can be removed once negative trait bounds are supported.
 #[cfg(doctest)]
 mod doctest {
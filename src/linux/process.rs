@@ -99,6 +99,9 @@ pub struct Process {
     old_written_bytes: u64,
     read_bytes: u64,
     written_bytes: u64,
+    thread_count: usize,
+    page_faults: u64,
+    old_page_faults: u64,
 }
 
 impl Process {
@@ -141,6 +144,9 @@ impl Process {
             old_written_bytes: 0,
             read_bytes: 0,
             written_bytes: 0,
+            thread_count: 0,
+            page_faults: 0,
+            old_page_faults: 0,
         }
     }
 }
@@ -183,6 +189,11 @@ impl ProcessExt for Process {
         self.memory
     }
 
+    fn memory_bytes(&self) -> u64 {
+        // The memory is computed from the number of pages, so no precision is lost.
+        self.memory * 1024
+    }
+
     fn virtual_memory(&self) -> u64 {
         self.virtual_memory
     }
@@ -213,8 +224,21 @@ impl ProcessExt for Process {
             total_written_bytes: self.written_bytes,
             read_bytes: self.read_bytes.saturating_sub(self.old_read_bytes),
             total_read_bytes: self.read_bytes,
+            ..DiskUsage::default()
         }
     }
+
+    fn thread_count(&self) -> usize {
+        self.thread_count
+    }
+
+    fn page_faults(&self) -> u64 {
+        self.page_faults
+    }
+
+    fn page_faults_delta(&self) -> u64 {
+        self.page_faults.saturating_sub(self.old_page_faults)
+    }
 }
 
 impl Drop for Process {
@@ -347,6 +371,11 @@ pub(crate) fn _get_process_data(
         if refresh_kind.disk_usage() {
             update_process_disk_activity(entry, path);
         }
+        if refresh_kind.cwd() {
+            let mut tmp = PathBuf::from(path);
+            tmp.push("cwd");
+            entry.cwd = realpath(&tmp);
+        }
         return Ok((None, pid));
     }
 
@@ -433,6 +462,7 @@ pub(crate) fn _get_process_data(
         info,
         refresh_kind,
     );
+    p.old_page_faults = p.page_faults;
     if refresh_kind.disk_usage() {
         update_process_disk_activity(&mut p, path);
     }
@@ -467,6 +497,13 @@ fn update_time_and_memory(
             u64::from_str(parts[14]).unwrap_or(0),
         );
         entry.run_time = uptime.saturating_sub(entry.start_time_without_boot_time);
+        if refresh_kind.threads() {
+            entry.thread_count = usize::from_str(parts[19]).unwrap_or(0);
+        }
+        // minflt + majflt
+        entry.old_page_faults = entry.page_faults;
+        entry.page_faults =
+            u64::from_str(parts[9]).unwrap_or(0) + u64::from_str(parts[11]).unwrap_or(0);
     }
     refresh_procs(
         entry,
@@ -5,14 +5,15 @@ use crate::{
     sys::{Component, Disk, Networks, Process, Processor},
 };
 use crate::{
-    DiskType, DiskUsage, LoadAvg, NetworksIter, Pid, ProcessRefreshKind, ProcessStatus,
-    RefreshKind, Signal, User,
+    DiskType, DiskUsage, LoadAvg, NetworksIter, Pid, PidExt, ProcessNode, ProcessRefreshKind,
+    ProcessStatus, RefreshKind, Signal, User,
 };
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::ffi::OsStr;
 use std::fmt::Debug;
 use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 /// Contains all the methods of the [`Disk`][crate::Disk] struct.
 ///
@@ -153,6 +154,12 @@ pub trait ProcessExt: Debug {
     /// To get the list of the supported signals on this system, use
     /// [`SystemExt::SUPPORTED_SIGNALS`].
     ///
+    /// On Windows, [`Signal::Kill`] terminates the process right away whereas [`Signal::Term`]
+    /// asks it to exit by closing its windows or, if it has none, by sending a Ctrl+Break event
+    /// to its console process group. The event is only sent if the process leads its own process
+    /// group on the console of the current process (like when it was started with the
+    /// `CREATE_NEW_PROCESS_GROUP` flag), otherwise `Some(false)` is returned.
+    ///
     /// ```no_run
     /// use sysinfo::{Pid, ProcessExt, Signal, System, SystemExt};
     ///
@@ -165,6 +172,45 @@ pub trait ProcessExt: Debug {
     /// ```
     fn kill_with(&self, signal: Signal) -> Option<bool>;
 
+    /// Suspends the process: none of its threads will run until [`ProcessExt::resume`] is
+    /// called. Returns `true` if the process was suspended.
+    ///
+    /// On Unix systems, [`Signal::Stop`] is sent to the process. On Windows, all the threads of
+    /// the process are suspended.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, ProcessExt, System, SystemExt};
+    ///
+    /// let s = System::new();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     if process.suspend() {
+    ///         // Do something while the process isn't running.
+    ///         process.resume();
+    ///     }
+    /// }
+    /// ```
+    fn suspend(&self) -> bool {
+        self.kill_with(Signal::Stop).unwrap_or(false)
+    }
+
+    /// Resumes a process previously suspended with [`ProcessExt::suspend`]. Returns `true` if
+    /// the process was resumed.
+    ///
+    /// On Unix systems, [`Signal::Continue`] is sent to the process. On Windows, all the threads
+    /// of the process are resumed.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, ProcessExt, System, SystemExt};
+    ///
+    /// let s = System::new();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     process.resume();
+    /// }
+    /// ```
+    fn resume(&self) -> bool {
+        self.kill_with(Signal::Continue).unwrap_or(false)
+    }
+
     /// Returns the name of the process.
     ///
     /// **⚠️ Important ⚠️**
@@ -234,6 +280,52 @@ pub trait ProcessExt: Debug {
     /// ```
     fn environ(&self) -> &[String];
 
+    /// Returns the environment variables of the process as `(name, value)` pairs.
+    ///
+    /// Unlike splitting the entries returned by [`ProcessExt::environ`] on every `=`, only the
+    /// first one separates the name from the value, so values containing `=` are kept as is.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, ProcessExt, System, SystemExt};
+    ///
+    /// let s = System::new();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     for (name, value) in process.environ_vars() {
+    ///         println!("{} => {}", name, value);
+    ///     }
+    /// }
+    /// ```
+    fn environ_vars(&self) -> Vec<(&str, &str)> {
+        self.environ()
+            .iter()
+            .filter_map(|var| crate::utils::split_environ_var(var))
+            .collect()
+    }
+
+    /// Returns the value of the environment variable `key` of the process.
+    ///
+    /// The name is case-insensitive on Windows (so `PATH` matches `Path`), like the system does,
+    /// and case-sensitive on the other platforms.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, ProcessExt, System, SystemExt};
+    ///
+    /// let s = System::new();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{:?}", process.environ_var("PATH"));
+    /// }
+    /// ```
+    fn environ_var(&self, key: &str) -> Option<&str> {
+        self.environ().iter().find_map(|var| {
+            let (name, value) = crate::utils::split_environ_var(var)?;
+            if crate::utils::environ_var_name_eq(name, key) {
+                Some(value)
+            } else {
+                None
+            }
+        })
+    }
+
     /// Returns the current working directory.
     ///
     /// ```no_run
@@ -270,6 +362,19 @@ pub trait ProcessExt: Debug {
     /// ```
     fn memory(&self) -> u64;
 
+    /// Returns the memory usage (in bytes), without the rounding done by
+    /// [`ProcessExt::memory`].
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, ProcessExt, System, SystemExt};
+    ///
+    /// let s = System::new();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{} bytes", process.memory_bytes());
+    /// }
+    /// ```
+    fn memory_bytes(&self) -> u64;
+
     /// Returns the virtual memory usage (in KB).
     ///
     /// ```no_run
@@ -318,6 +423,27 @@ pub trait ProcessExt: Debug {
     /// ```
     fn start_time(&self) -> u64;
 
+    /// Returns the time where the process was started as a [`SystemTime`], or `None` if it
+    /// couldn't be retrieved.
+    ///
+    /// It is based on [`ProcessExt::start_time`], except on Windows where the full precision
+    /// of the creation time is kept.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, ProcessExt, System, SystemExt};
+    ///
+    /// let s = System::new();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("Started at {:?}", process.start_time_system());
+    /// }
+    /// ```
+    fn start_time_system(&self) -> Option<SystemTime> {
+        match self.start_time() {
+            0 => None,
+            start => Some(UNIX_EPOCH + Duration::from_secs(start)),
+        }
+    }
+
     /// Returns for how much time the process has been running (in seconds).
     ///
     /// ```no_run
@@ -330,11 +456,11 @@ pub trait ProcessExt: Debug {
     /// ```
     fn run_time(&self) -> u64;
 
-    /// Returns the total CPU usage (in %). Notice that it might be bigger than 100 if run on a
-    /// multicore machine.
+    /// Returns the total CPU usage (in %), where 100% means that one logical processor was fully
+    /// used. Notice that it might be bigger than 100 if run on a multicore machine.
     ///
     /// If you want a value between 0% and 100%, divide the returned value by the number of CPU
-    /// processors.
+    /// processors (on Windows, `Process::cpu_usage_machine_share` returns it directly).
     ///
     /// **Warning**: If you want accurate CPU usage number, better leave a bit of time
     /// between two calls of this method (200 ms for example).
@@ -353,6 +479,9 @@ pub trait ProcessExt: Debug {
     ///
     /// ⚠️ On Windows and FreeBSD, this method actually returns **ALL** I/O read and written bytes.
     ///
+    /// If the counters of the process decreased since the previous refresh (which shouldn't
+    /// happen), the numbers of bytes since the last refresh are `0`.
+    ///
     /// ```no_run
     /// use sysinfo::{Pid, ProcessExt, System, SystemExt};
     ///
@@ -370,6 +499,53 @@ pub trait ProcessExt: Debug {
     /// }
     /// ```
     fn disk_usage(&self) -> DiskUsage;
+
+    /// Returns the number of threads of the process.
+    ///
+    /// It's only updated when the process is refreshed with
+    /// [`ProcessRefreshKind::threads`][crate::ProcessRefreshKind::threads] set, and it's `0` if
+    /// it couldn't be retrieved (like for protected processes on Windows).
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, ProcessExt, ProcessRefreshKind, System, SystemExt};
+    ///
+    /// let mut s = System::new();
+    /// s.refresh_process_specifics(Pid::from(1337), ProcessRefreshKind::new().with_threads());
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{} threads", process.thread_count());
+    /// }
+    /// ```
+    fn thread_count(&self) -> usize;
+
+    /// Returns the number of page faults of the process since it started, including the ones
+    /// which were resolved without reading the disk.
+    ///
+    /// On Windows and macOS, the counter is 32-bit so it wraps around for long-lived processes
+    /// causing a lot of page faults: [`ProcessExt::page_faults_delta`] handles it.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, ProcessExt, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{} page faults", process.page_faults());
+    /// }
+    /// ```
+    fn page_faults(&self) -> u64;
+
+    /// Returns the number of page faults of the process since the previous refresh. It's `0`
+    /// until the process has been refreshed twice.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, ProcessExt, System, SystemExt};
+    ///
+    /// let mut s = System::new_all();
+    /// s.refresh_process(Pid::from(1337));
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{} new page faults", process.page_faults_delta());
+    /// }
+    /// ```
+    fn page_faults_delta(&self) -> u64;
 }
 
 /// Contains all the methods of the [`Processor`][crate::Processor] struct.
@@ -859,6 +1035,353 @@ pub trait SystemExt: Sized + Debug + Default + Send + Sync {
         )
     }
 
+    /// Returns the processes sorted by ascending [`ProcessExt::start_time`]. Processes started
+    /// at the same time are sorted by [`Pid`].
+    ///
+    /// ```no_run
+    /// use sysinfo::{ProcessExt, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// for process in s.processes_by_start_time() {
+    ///     println!("{} {} {}", process.start_time(), process.pid(), process.name());
+    /// }
+    /// ```
+    fn processes_by_start_time(&self) -> Vec<&Process> {
+        let mut processes = self.processes().values().collect::<Vec<_>>();
+        processes.sort_unstable_by_key(|p| (p.start_time(), p.pid()));
+        processes
+    }
+
+    /// Returns the processes whose executable is `path`.
+    ///
+    /// On Windows, the comparison is case-insensitive.
+    ///
+    /// ```no_run
+    /// use sysinfo::{ProcessExt, System, SystemExt};
+    /// use std::path::Path;
+    ///
+    /// let s = System::new_all();
+    /// for process in s.processes_by_exe_path(Path::new("/usr/bin/htop")) {
+    ///     println!("{} {}", process.pid(), process.name());
+    /// }
+    /// ```
+    fn processes_by_exe_path(&self, path: &Path) -> Vec<&Process> {
+        if cfg!(windows) {
+            let path = path.to_string_lossy().to_lowercase();
+            self.processes()
+                .values()
+                .filter(|p| p.exe().to_string_lossy().to_lowercase() == path)
+                .collect()
+        } else {
+            self.processes()
+                .values()
+                .filter(|p| p.exe() == path)
+                .collect()
+        }
+    }
+
+    /// Returns the sum of the memory usage (in KB) of the processes whose executable is `path`,
+    /// as matched by [`SystemExt::processes_by_exe_path`].
+    ///
+    /// This is useful for applications running as multiple processes, like browsers. The pages
+    /// shared between these processes (like the ones of the executable and of the libraries) are
+    /// counted once per process, so the result is higher than the memory actually used. On
+    /// Windows, summing `Process::private_working_set` instead doesn't have this issue but it's
+    /// much slower to compute.
+    ///
+    /// ```no_run
+    /// use sysinfo::{System, SystemExt};
+    /// use std::path::Path;
+    ///
+    /// let s = System::new_all();
+    /// println!("{} KB", s.total_memory_by_exe(Path::new("/usr/bin/firefox")));
+    /// ```
+    fn total_memory_by_exe(&self, path: &Path) -> u64 {
+        self.processes_by_exe_path(path)
+            .iter()
+            .map(|p| p.memory())
+            .sum()
+    }
+
+    /// Returns the sum of the memory usage (in KB) of the processes with exactly the given
+    /// `name`. The shared pages are counted once per process, like with
+    /// [`SystemExt::total_memory_by_exe`].
+    ///
+    /// ```no_run
+    /// use sysinfo::{System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// println!("{} KB", s.total_memory_by_name("chrome.exe"));
+    /// ```
+    fn total_memory_by_name(&self, name: &str) -> u64 {
+        self.processes_by_exact_name(name).map(|p| p.memory()).sum()
+    }
+
+    /// Returns the process corresponding to `child`, a process spawned with
+    /// [`std::process::Command`].
+    ///
+    /// To make sure the PID wasn't reused by another process since the last refresh, `None` is
+    /// returned if the found process has another parent than the current process or if it
+    /// started before the current process.
+    ///
+    /// ```no_run
+    /// use sysinfo::{ProcessExt, System, SystemExt};
+    ///
+    /// let child = std::process::Command::new("ls").spawn().unwrap();
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process_for_child(&child) {
+    ///     println!("{}", process.name());
+    /// }
+    /// ```
+    fn process_for_child(&self, child: &std::process::Child) -> Option<&Process> {
+        let process = self.process(Pid::from_u32(child.id()))?;
+        if let Ok(current_pid) = crate::get_current_pid() {
+            if matches!(process.parent(), Some(parent) if parent != current_pid) {
+                return None;
+            }
+            if let Some(current) = self.process(current_pid) {
+                if process.start_time() < current.start_time() {
+                    return None;
+                }
+            }
+        }
+        Some(process)
+    }
+
+    /// Returns the PID of the parent of the process `pid`, after checking that it's still the
+    /// same process.
+    ///
+    /// [`ProcessExt::parent`] is read once and PIDs get reused, so it can be the PID of a process
+    /// unrelated to `pid`. As a process can't be the parent of a process started before it,
+    /// `None` is returned if the parent started after the process, as well as if the process or
+    /// its parent isn't in the list.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(parent) = s.parent_checked(Pid::from(1337)) {
+    ///     println!("spawned by {}", parent);
+    /// }
+    /// ```
+    fn parent_checked(&self, pid: Pid) -> Option<Pid> {
+        let process = self.process(pid)?;
+        let parent = process.parent()?;
+        if self.process(parent)?.start_time() > process.start_time() {
+            return None;
+        }
+        Some(parent)
+    }
+
+    /// Returns the name of the parent of the process `pid`.
+    ///
+    /// `None` is returned if the process or its parent isn't in the list, or if the parent
+    /// started after the process, which means the parent's PID was reused by another process.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(name) = s.parent_name(Pid::from(1337)) {
+    ///     println!("spawned by {}", name);
+    /// }
+    /// ```
+    fn parent_name(&self, pid: Pid) -> Option<String> {
+        let parent = self.parent_checked(pid)?;
+        self.process(parent).map(|parent| parent.name().to_owned())
+    }
+
+    /// Returns the processes as a forest: each root node is a process without a known parent and
+    /// holds its child processes recursively. Roots and children are sorted by PID.
+    ///
+    /// A process is a root if its parent isn't in the list (or is a pseudo-process with the PID
+    /// `0`), if it's its own parent or if its parent started after it, which means the parent's
+    /// PID was reused by another process. Processes whose parents form a cycle are added as roots
+    /// too, so every process appears exactly once.
+    ///
+    /// ```no_run
+    /// use sysinfo::{ProcessNode, System, SystemExt};
+    ///
+    /// fn print(node: &ProcessNode, depth: usize) {
+    ///     println!("{:width$}{}", "", node.pid, width = depth * 2);
+    ///     for child in &node.children {
+    ///         print(child, depth + 1);
+    ///     }
+    /// }
+    ///
+    /// let s = System::new_all();
+    /// for root in s.process_forest() {
+    ///     print(&root, 0);
+    /// }
+    /// ```
+    fn process_forest(&self) -> Vec<ProcessNode> {
+        fn build(
+            pid: Pid,
+            children: &HashMap<Pid, Vec<Pid>>,
+            visited: &mut HashSet<Pid>,
+        ) -> ProcessNode {
+            visited.insert(pid);
+            let mut node = ProcessNode {
+                pid,
+                children: Vec::new(),
+            };
+            if let Some(child_pids) = children.get(&pid) {
+                for child in child_pids {
+                    if !visited.contains(child) {
+                        node.children.push(build(*child, children, visited));
+                    }
+                }
+            }
+            node
+        }
+
+        let processes = self.processes();
+        let mut pids = processes.keys().copied().collect::<Vec<_>>();
+        pids.sort_unstable();
+
+        let mut roots = Vec::new();
+        let mut children: HashMap<Pid, Vec<Pid>> = HashMap::new();
+        for pid in pids.iter() {
+            let parent = self
+                .parent_checked(*pid)
+                .filter(|parent| parent != pid && parent.as_u32() != 0);
+            match parent {
+                Some(parent) => children.entry(parent).or_default().push(*pid),
+                None => roots.push(*pid),
+            }
+        }
+
+        let mut visited = HashSet::with_capacity(pids.len());
+        let mut forest = roots
+            .into_iter()
+            .map(|pid| build(pid, &children, &mut visited))
+            .collect::<Vec<_>>();
+        // Whatever is left is part of a cycle.
+        for pid in pids {
+            if !visited.contains(&pid) {
+                forest.push(build(pid, &children, &mut visited));
+            }
+        }
+        forest.sort_unstable_by_key(|node| node.pid);
+        forest
+    }
+
+    /// Returns the direct children of the process `pid`, sorted by PID.
+    ///
+    /// Like with [`SystemExt::parent_checked`], the processes started before `pid` aren't
+    /// considered as its children.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, ProcessExt, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// for child in s.process_children(Pid::from(1337)) {
+    ///     println!("{}", child.name());
+    /// }
+    /// ```
+    fn process_children(&self, pid: Pid) -> Vec<&Process> {
+        let mut children = self
+            .processes()
+            .values()
+            .filter(|p| p.pid() != pid && self.parent_checked(p.pid()) == Some(pid))
+            .collect::<Vec<_>>();
+        children.sort_unstable_by_key(|p| p.pid());
+        children
+    }
+
+    /// Returns the descendants of the process `pid` (its children, their children and so on),
+    /// closest ones first. The process itself isn't included.
+    ///
+    /// Each process is returned only once, even if the parent links form a cycle.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, ProcessExt, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// let memory: u64 = s
+    ///     .process_descendants(Pid::from(1337))
+    ///     .iter()
+    ///     .map(|p| p.memory())
+    ///     .sum();
+    /// println!("{} KB", memory);
+    /// ```
+    fn process_descendants(&self, pid: Pid) -> Vec<&Process> {
+        let mut children: HashMap<Pid, Vec<&Process>> = HashMap::new();
+        for process in self.processes().values() {
+            if let Some(parent) = self.parent_checked(process.pid()) {
+                children.entry(parent).or_default().push(process);
+            }
+        }
+        for list in children.values_mut() {
+            list.sort_unstable_by_key(|p| p.pid());
+        }
+
+        let mut visited = HashSet::new();
+        visited.insert(pid);
+        let mut descendants: Vec<&Process> = Vec::new();
+        let mut current = pid;
+        let mut next = 0;
+        loop {
+            if let Some(list) = children.get(&current) {
+                for child in list {
+                    if visited.insert(child.pid()) {
+                        descendants.push(child);
+                    }
+                }
+            }
+            match descendants.get(next) {
+                Some(p) => current = p.pid(),
+                None => break,
+            }
+            next += 1;
+        }
+        descendants
+    }
+
+    /// Sends `signal` to the process `pid` and to all its descendants (as returned by
+    /// [`SystemExt::process_descendants`]), the deepest ones first so that no process is orphaned
+    /// before being killed. It uses [`ProcessExt::kill_with`] for each process.
+    ///
+    /// A process which exited before it could be killed is considered as killed. The processes
+    /// spawned after the last refresh aren't known though, so they might survive.
+    ///
+    /// Returns `false` if `pid` isn't in the list or if any of the processes couldn't be killed.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, Signal, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if !s.kill_process_tree(Pid::from(1337), Signal::Kill) {
+    ///     eprintln!("some processes couldn't be killed");
+    /// }
+    /// ```
+    fn kill_process_tree(&self, pid: Pid, signal: Signal) -> bool {
+        let process = match self.process(pid) {
+            Some(process) => process,
+            None => return false,
+        };
+        let kill = |process: &Process| match process.kill_with(signal) {
+            Some(true) => true,
+            Some(false) => {
+                // The process might have exited since the last refresh (or even have been
+                // replaced by another one with the same PID).
+                let mut s = Self::new();
+                if !s.refresh_process_specifics(process.pid(), ProcessRefreshKind::new()) {
+                    return true;
+                }
+                let pid = process.pid();
+                !matches!(s.process(pid), Some(p) if p.start_time() == process.start_time())
+            }
+            None => false,
+        };
+        let mut success = true;
+        // Every process comes after its parent in the descendants.
+        for descendant in self.process_descendants(pid).into_iter().rev() {
+            success &= kill(descendant);
+        }
+        kill(process) && success
+    }
+
     /// Returns "global" processors information (aka the addition of all the processors).
     ///
     /// To have up-to-date information, you need to call [`SystemExt::refresh_cpu`] or
@@ -59,3 +59,20 @@ where
 {
     val.into_iter()
 }
+
+/// Splits an environment variable (in the `NAME=VALUE` form) on its first `=`. On Windows, the
+/// variables storing the current directory of each drive (like `=C:=C:\`) start with a `=`,
+/// so the separator is looked for after the first character.
+pub(crate) fn split_environ_var(var: &str) -> Option<(&str, &str)> {
+    let pos = var.get(1..)?.find('=')? + 1;
+    Some((&var[..pos], &var[pos + 1..]))
+}
+
+/// Environment variable names are case-insensitive on Windows only.
+pub(crate) fn environ_var_name_eq(name: &str, other: &str) -> bool {
+    if cfg!(target_os = "windows") {
+        name.eq_ignore_ascii_case(other)
+    } else {
+        name == other
+    }
+}
@@ -0,0 +1,127 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use crate::Pid;
+
+use std::future::Future;
+use std::pin::Pin;
+use std::ptr::null_mut;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+use winapi::shared::minwindef::{DWORD, FALSE};
+use winapi::shared::ntdef::{BOOLEAN, PVOID};
+use winapi::um::handleapi::{CloseHandle, INVALID_HANDLE_VALUE};
+use winapi::um::processthreadsapi::{GetExitCodeProcess, OpenProcess};
+use winapi::um::threadpoollegacyapiset::UnregisterWaitEx;
+use winapi::um::winbase::{RegisterWaitForSingleObject, INFINITE};
+use winapi::um::winnt::{
+    HANDLE, PROCESS_QUERY_LIMITED_INFORMATION, SYNCHRONIZE, WT_EXECUTEONLYONCE,
+};
+
+#[derive(Default)]
+struct State {
+    exited: bool,
+    waker: Option<Waker>,
+}
+
+/// Future resolving when a process exits. It's returned by `Process::exited`.
+///
+/// The wait is done by the system thread pool, so no thread is blocked while waiting.
+pub(crate) struct ProcessExit {
+    process: HANDLE,
+    wait: HANDLE,
+    state: *const Mutex<State>,
+}
+
+// The handles and the state can be used from any thread.
+unsafe impl Send for ProcessExit {}
+unsafe impl Sync for ProcessExit {}
+
+unsafe extern "system" fn on_exit(context: PVOID, _timed_out: BOOLEAN) {
+    let state = &*(context as *const Mutex<State>);
+    if let Ok(mut state) = state.lock() {
+        state.exited = true;
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl ProcessExit {
+    /// Returns a `ProcessExit` resolving to `None` right away.
+    pub(crate) fn none() -> ProcessExit {
+        ProcessExit {
+            process: null_mut(),
+            wait: null_mut(),
+            state: Arc::into_raw(Arc::new(Mutex::new(State::default()))),
+        }
+    }
+
+    pub(crate) fn new(pid: Pid) -> ProcessExit {
+        let mut exit = ProcessExit::none();
+        unsafe {
+            exit.process = OpenProcess(
+                SYNCHRONIZE | PROCESS_QUERY_LIMITED_INFORMATION,
+                FALSE,
+                pid.0 as DWORD,
+            );
+            if exit.process.is_null() {
+                sysinfo_debug!("OpenProcess failed for process {}", pid);
+                return exit;
+            }
+            // The state is kept alive until the wait is unregistered, in `drop`.
+            if RegisterWaitForSingleObject(
+                &mut exit.wait,
+                exit.process,
+                Some(on_exit),
+                exit.state as PVOID,
+                INFINITE,
+                WT_EXECUTEONLYONCE,
+            ) == FALSE
+            {
+                sysinfo_debug!("RegisterWaitForSingleObject failed for process {}", pid);
+                exit.wait = null_mut();
+            }
+        }
+        exit
+    }
+}
+
+impl Future for ProcessExit {
+    type Output = Option<u32>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.wait.is_null() {
+            return Poll::Ready(None);
+        }
+        let state = unsafe { &*self.state };
+        let mut state = match state.lock() {
+            Ok(state) => state,
+            Err(_) => return Poll::Ready(None),
+        };
+        if !state.exited {
+            state.waker = Some(cx.waker().clone());
+            return Poll::Pending;
+        }
+        let mut exit_code = 0;
+        if unsafe { GetExitCodeProcess(self.process, &mut exit_code) } == FALSE {
+            return Poll::Ready(None);
+        }
+        Poll::Ready(Some(exit_code))
+    }
+}
+
+impl Drop for ProcessExit {
+    fn drop(&mut self) {
+        unsafe {
+            if !self.wait.is_null() {
+                // Waits for the callback to complete if it's running.
+                UnregisterWaitEx(self.wait, INVALID_HANDLE_VALUE);
+            }
+            if !self.process.is_null() {
+                CloseHandle(self.process);
+            }
+            drop(Arc::from_raw(self.state));
+        }
+    }
+}
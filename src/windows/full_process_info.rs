@@ -0,0 +1,118 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use crate::Pid;
+
+use std::mem::size_of;
+
+use ntapi::ntexapi::{
+    NtQuerySystemInformation, SystemFullProcessInformation, SystemProcessInformation,
+    SystemRootSiloInformation, SYSTEM_EXTENDED_THREAD_INFORMATION, SYSTEM_PROCESS_INFORMATION,
+    SYSTEM_PROCESS_INFORMATION_EXTENSION, SYSTEM_ROOT_SILO_INFORMATION, SYSTEM_THREAD_INFORMATION,
+};
+use winapi::shared::ntdef::{NT_SUCCESS, PVOID, ULONG};
+use winapi::shared::ntstatus::STATUS_INFO_LENGTH_MISMATCH;
+
+// Calls `NtQuerySystemInformation` with `class`, growing the buffer until it's big enough.
+unsafe fn query_system_information(class: ULONG, mut buffer_size: usize) -> Option<Vec<u8>> {
+    loop {
+        let mut buffer: Vec<u8> = vec![0; buffer_size];
+        let mut cb_needed: ULONG = 0;
+        let ntstatus = NtQuerySystemInformation(
+            class,
+            buffer.as_mut_ptr() as PVOID,
+            buffer_size as ULONG,
+            &mut cb_needed,
+        );
+
+        if ntstatus == STATUS_INFO_LENGTH_MISMATCH {
+            // New processes might be started between two calls so we take some margin.
+            buffer_size = std::cmp::max(buffer_size * 2, cb_needed as usize + 10 * 1024);
+            continue;
+        }
+        if !NT_SUCCESS(ntstatus) {
+            sysinfo_debug!("NtQuerySystemInformation({}) failed: {}", class, ntstatus);
+            return None;
+        }
+        buffer.truncate(cb_needed as usize);
+        return Some(buffer);
+    }
+}
+
+/// Returns the extended information of the process `pid`, which the kernel appends after the
+/// threads information when `SystemFullProcessInformation` is used.
+///
+/// This information class requires administrator privileges.
+#[allow(clippy::cast_ptr_alignment)]
+pub(crate) fn get_process_extension(pid: Pid) -> Option<SYSTEM_PROCESS_INFORMATION_EXTENSION> {
+    unsafe {
+        let buffer = query_system_information(SystemFullProcessInformation, 1024 * 1024)?;
+        let mut offset = 0;
+
+        loop {
+            if offset + size_of::<SYSTEM_PROCESS_INFORMATION>() > buffer.len() {
+                return None;
+            }
+            let pi = &*(buffer.as_ptr().add(offset) as *const SYSTEM_PROCESS_INFORMATION);
+            if pi.UniqueProcessId as usize == pid.0 {
+                // `Threads` is declared as an array of one `SYSTEM_THREAD_INFORMATION` but with
+                // this information class, it contains `SYSTEM_EXTENDED_THREAD_INFORMATION`s.
+                let ext_offset = offset + size_of::<SYSTEM_PROCESS_INFORMATION>()
+                    - size_of::<SYSTEM_THREAD_INFORMATION>()
+                    + pi.NumberOfThreads as usize * size_of::<SYSTEM_EXTENDED_THREAD_INFORMATION>();
+                if ext_offset + size_of::<SYSTEM_PROCESS_INFORMATION_EXTENSION>() > buffer.len() {
+                    return None;
+                }
+                return Some(std::ptr::read_unaligned(
+                    buffer.as_ptr().add(ext_offset) as *const SYSTEM_PROCESS_INFORMATION_EXTENSION
+                ));
+            }
+            if pi.NextEntryOffset == 0 {
+                return None;
+            }
+            offset += pi.NextEntryOffset as usize;
+        }
+    }
+}
+
+/// Returns the IDs of the server silos (the Windows containers) running on the system.
+#[allow(clippy::cast_ptr_alignment)]
+pub(crate) fn get_server_silo_ids() -> Option<Vec<u32>> {
+    unsafe {
+        let buffer = query_system_information(SystemRootSiloInformation, 1024)?;
+        if buffer.len() < size_of::<SYSTEM_ROOT_SILO_INFORMATION>() {
+            // No silo.
+            return Some(Vec::new());
+        }
+        let info = &*(buffer.as_ptr() as *const SYSTEM_ROOT_SILO_INFORMATION);
+        Some(
+            std::slice::from_raw_parts(info.SiloIdList.as_ptr(), info.NumberOfSilos as usize)
+                .to_vec(),
+        )
+    }
+}
+
+/// Returns the information of the threads of the process `pid`.
+#[allow(clippy::cast_ptr_alignment)]
+pub(crate) fn get_process_threads(pid: Pid) -> Option<Vec<SYSTEM_THREAD_INFORMATION>> {
+    unsafe {
+        let buffer = query_system_information(SystemProcessInformation, 512 * 1024)?;
+        let mut offset = 0;
+
+        loop {
+            if offset + size_of::<SYSTEM_PROCESS_INFORMATION>() > buffer.len() {
+                return None;
+            }
+            let pi = &*(buffer.as_ptr().add(offset) as *const SYSTEM_PROCESS_INFORMATION);
+            if pi.UniqueProcessId as usize == pid.0 {
+                return Some(
+                    std::slice::from_raw_parts(pi.Threads.as_ptr(), pi.NumberOfThreads as usize)
+                        .to_vec(),
+                );
+            }
+            if pi.NextEntryOffset == 0 {
+                return None;
+            }
+            offset += pi.NextEntryOffset as usize;
+        }
+    }
+}
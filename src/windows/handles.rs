@@ -0,0 +1,440 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use crate::Pid;
+
+use std::collections::{HashMap, HashSet};
+use std::mem::size_of;
+use std::ptr::null_mut;
+use std::sync::{mpsc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use ntapi::ntexapi::{
+    NtQuerySystemInformation, SystemExtendedHandleInformation, SYSTEM_HANDLE_INFORMATION_EX,
+    SYSTEM_HANDLE_TABLE_ENTRY_INFO_EX,
+};
+use ntapi::ntobapi::{
+    NtQueryObject, ObjectNameInformation, ObjectTypesInformation, OBJECT_NAME_INFORMATION,
+    OBJECT_TYPES_INFORMATION, OBJECT_TYPE_INFORMATION,
+};
+use once_cell::sync::Lazy;
+use winapi::shared::minwindef::{DWORD, FALSE};
+use winapi::shared::ntdef::{NT_SUCCESS, PVOID, ULONG};
+use winapi::shared::ntstatus::{STATUS_BUFFER_OVERFLOW, STATUS_INFO_LENGTH_MISMATCH};
+use winapi::um::handleapi::{CloseHandle, DuplicateHandle};
+use winapi::um::jobapi::IsProcessInJob;
+use winapi::um::processthreadsapi::{
+    GetCurrentProcess, GetCurrentProcessId, GetProcessId, OpenProcess,
+};
+use winapi::um::winnt::{
+    DUPLICATE_SAME_ACCESS, HANDLE, PROCESS_DUP_HANDLE, PROCESS_QUERY_LIMITED_INFORMATION,
+};
+
+// Retrieving the name of some file handles (synchronous named pipes for example) can block
+// forever, so it's done in another thread which is given up on after this delay.
+const NAME_QUERY_TIMEOUT: Duration = Duration::from_millis(100);
+
+// Thread retrieving the names of the handles which might block. It's reused for all of them and
+// only replaced when it got stuck on one.
+struct NameQueryWorker {
+    // `HANDLE` isn't `Send`, so the handles are sent as `usize`. They're closed by the thread.
+    handles: mpsc::Sender<usize>,
+    names: mpsc::Receiver<Option<String>>,
+}
+
+impl NameQueryWorker {
+    fn new() -> Self {
+        let (handles, receiver) = mpsc::channel::<usize>();
+        let (sender, names) = mpsc::channel();
+        thread::spawn(move || {
+            for handle in receiver {
+                let name = unsafe {
+                    let name = query_object_name(handle as HANDLE);
+                    CloseHandle(handle as HANDLE);
+                    name
+                };
+                // The worker was replaced while this thread was stuck.
+                if sender.send(name).is_err() {
+                    break;
+                }
+            }
+        });
+        Self { handles, names }
+    }
+}
+
+static NAME_QUERY_WORKER: Lazy<Mutex<Option<NameQueryWorker>>> = Lazy::new(|| Mutex::new(None));
+
+// The list of the object types doesn't change while the system is running so we only need to
+// retrieve it once.
+static OBJECT_TYPES: Lazy<Vec<(u16, String)>> = Lazy::new(|| unsafe { get_object_types() });
+
+#[inline]
+fn align_up(value: usize) -> usize {
+    let align = size_of::<usize>();
+    (value + align - 1) & !(align - 1)
+}
+
+#[allow(clippy::cast_ptr_alignment)]
+unsafe fn get_object_types() -> Vec<(u16, String)> {
+    let mut buffer_size: usize = 16 * 1024;
+
+    loop {
+        let mut buffer: Vec<u8> = vec![0; buffer_size];
+        let mut cb_needed: ULONG = 0;
+        let ntstatus = NtQueryObject(
+            null_mut(),
+            ObjectTypesInformation,
+            buffer.as_mut_ptr() as PVOID,
+            buffer_size as ULONG,
+            &mut cb_needed,
+        );
+
+        if ntstatus == STATUS_INFO_LENGTH_MISMATCH {
+            // The returned length isn't always reliable for this information class.
+            buffer_size = std::cmp::max(buffer_size * 2, cb_needed as usize);
+            continue;
+        }
+        if !NT_SUCCESS(ntstatus) {
+            sysinfo_debug!("NtQueryObject(ObjectTypesInformation) failed: {}", ntstatus);
+            return Vec::new();
+        }
+
+        let nb_types = (*(buffer.as_ptr() as *const OBJECT_TYPES_INFORMATION)).NumberOfTypes;
+        let mut types = Vec::with_capacity(nb_types as usize);
+        let mut offset = align_up(size_of::<OBJECT_TYPES_INFORMATION>());
+
+        for pos in 0..nb_types {
+            if offset + size_of::<OBJECT_TYPE_INFORMATION>() > buffer.len() {
+                break;
+            }
+            let info = &*(buffer.as_ptr().add(offset) as *const OBJECT_TYPE_INFORMATION);
+            let name = if info.TypeName.Buffer.is_null() {
+                String::new()
+            } else {
+                String::from_utf16_lossy(std::slice::from_raw_parts(
+                    info.TypeName.Buffer,
+                    info.TypeName.Length as usize / size_of::<u16>(),
+                ))
+            };
+            // `TypeIndex` is only filled since Windows 8.1. Before that, the indexes started at 2
+            // and followed the listing order.
+            let index = if info.TypeIndex != 0 {
+                info.TypeIndex as u16
+            } else {
+                pos as u16 + 2
+            };
+            types.push((index, name));
+            offset = align_up(
+                offset
+                    + size_of::<OBJECT_TYPE_INFORMATION>()
+                    + info.TypeName.MaximumLength as usize,
+            );
+        }
+        return types;
+    }
+}
+
+/// Returns the index of the object type named `type_name` (case-insensitive).
+pub(crate) fn get_object_type_index(type_name: &str) -> Option<u16> {
+    OBJECT_TYPES
+        .iter()
+        .find(|(_, name)| name.eq_ignore_ascii_case(type_name))
+        .map(|(index, _)| *index)
+}
+
+/// Returns the name of the object type with the index `index`.
+pub(crate) fn get_object_type_name(index: u16) -> Option<&'static str> {
+    OBJECT_TYPES
+        .iter()
+        .find(|(i, _)| *i == index)
+        .map(|(_, name)| name.as_str())
+}
+
+#[allow(clippy::cast_ptr_alignment)]
+unsafe fn query_object_name(handle: HANDLE) -> Option<String> {
+    let mut buffer_size: usize = 1024;
+
+    loop {
+        let mut buffer: Vec<u8> = vec![0; buffer_size];
+        let mut cb_needed: ULONG = 0;
+        let ntstatus = NtQueryObject(
+            handle,
+            ObjectNameInformation,
+            buffer.as_mut_ptr() as PVOID,
+            buffer_size as ULONG,
+            &mut cb_needed,
+        );
+
+        if ntstatus == STATUS_INFO_LENGTH_MISMATCH || ntstatus == STATUS_BUFFER_OVERFLOW {
+            if cb_needed as usize <= buffer_size {
+                return None;
+            }
+            buffer_size = cb_needed as usize;
+            continue;
+        }
+        if !NT_SUCCESS(ntstatus) {
+            return None;
+        }
+
+        let name = &(*(buffer.as_ptr() as *const OBJECT_NAME_INFORMATION)).Name;
+        if name.Buffer.is_null() || name.Length == 0 {
+            return None;
+        }
+        return Some(String::from_utf16_lossy(std::slice::from_raw_parts(
+            name.Buffer,
+            name.Length as usize / size_of::<u16>(),
+        )));
+    }
+}
+
+/// Returns the name of the object targeted by the handle `handle_value` of `process`, which
+/// must have been opened with `PROCESS_DUP_HANDLE`.
+///
+/// If `may_block` is `true`, the name is retrieved in a worker thread.
+pub(crate) fn get_handle_name(
+    process: HANDLE,
+    handle_value: usize,
+    may_block: bool,
+) -> Option<String> {
+    unsafe {
+        let mut handle = null_mut();
+        if DuplicateHandle(
+            process,
+            handle_value as HANDLE,
+            GetCurrentProcess(),
+            &mut handle,
+            0,
+            FALSE,
+            DUPLICATE_SAME_ACCESS,
+        ) == FALSE
+        {
+            return None;
+        }
+        if !may_block {
+            let name = query_object_name(handle);
+            CloseHandle(handle);
+            return name;
+        }
+        let mut worker = match NAME_QUERY_WORKER.lock() {
+            Ok(worker) => worker,
+            Err(_) => {
+                CloseHandle(handle);
+                return None;
+            }
+        };
+        let name = {
+            let current = worker.get_or_insert_with(NameQueryWorker::new);
+            if current.handles.send(handle as usize).is_err() {
+                // The thread exited, so it's replaced on the next call.
+                *worker = None;
+                CloseHandle(handle);
+                return None;
+            }
+            current.names.recv_timeout(NAME_QUERY_TIMEOUT)
+        };
+        match name {
+            Ok(name) => name,
+            Err(_) => {
+                // The thread is stuck on this handle: it'll close it and exit if it ever returns.
+                *worker = None;
+                None
+            }
+        }
+    }
+}
+
+/// Returns a snapshot of all the handles opened on the system.
+#[allow(clippy::cast_ptr_alignment)]
+pub(crate) fn get_system_handles() -> Option<Vec<SYSTEM_HANDLE_TABLE_ENTRY_INFO_EX>> {
+    // Most systems have a few hundred thousands handles opened.
+    let mut buffer_size: usize = 4 * 1024 * 1024;
+
+    unsafe {
+        loop {
+            let mut buffer: Vec<u8> = vec![0; buffer_size];
+            let mut cb_needed: ULONG = 0;
+            let ntstatus = NtQuerySystemInformation(
+                SystemExtendedHandleInformation,
+                buffer.as_mut_ptr() as PVOID,
+                buffer_size as ULONG,
+                &mut cb_needed,
+            );
+
+            if ntstatus == STATUS_INFO_LENGTH_MISMATCH {
+                // New handles might be opened between two calls so we take some margin.
+                buffer_size = std::cmp::max(buffer_size * 2, cb_needed as usize + 64 * 1024);
+                continue;
+            }
+            if !NT_SUCCESS(ntstatus) {
+                sysinfo_debug!(
+                    "NtQuerySystemInformation(SystemExtendedHandleInformation) failed: {}",
+                    ntstatus
+                );
+                return None;
+            }
+
+            let info = &*(buffer.as_ptr() as *const SYSTEM_HANDLE_INFORMATION_EX);
+            return Some(
+                std::slice::from_raw_parts(info.Handles.as_ptr(), info.NumberOfHandles).to_vec(),
+            );
+        }
+    }
+}
+
+/// Returns the handles of the system handle table which belong to the process `pid`.
+pub(crate) fn get_process_handles(pid: Pid) -> Option<Vec<SYSTEM_HANDLE_TABLE_ENTRY_INFO_EX>> {
+    get_system_handles().map(|handles| {
+        handles
+            .into_iter()
+            .filter(|h| h.UniqueProcessId == pid.0)
+            .collect()
+    })
+}
+
+/// Returns handles to the job objects which contain `process`, which must have been opened with
+/// `PROCESS_QUERY_INFORMATION` or `PROCESS_QUERY_LIMITED_INFORMATION`.
+///
+/// There is no API to get the jobs of a process so the handles to job objects opened by all the
+/// processes are checked. The returned handles must be closed by the caller.
+pub(crate) fn get_process_jobs(process: HANDLE) -> Vec<HANDLE> {
+    let job_type = match get_object_type_index("Job") {
+        Some(job_type) => job_type,
+        None => return Vec::new(),
+    };
+    let handles = match get_system_handles() {
+        Some(handles) => handles,
+        None => return Vec::new(),
+    };
+    let mut owners: HashMap<usize, HANDLE> = HashMap::new();
+    let mut objects = HashSet::new();
+    let mut jobs = Vec::new();
+
+    unsafe {
+        for h in handles.iter().filter(|h| h.ObjectTypeIndex == job_type) {
+            // The same job can be opened multiple times. The object address is only available
+            // with enough privileges though.
+            if !h.Object.is_null() && objects.contains(&h.Object) {
+                continue;
+            }
+            let owner = *owners.entry(h.UniqueProcessId).or_insert_with(|| {
+                OpenProcess(PROCESS_DUP_HANDLE, FALSE, h.UniqueProcessId as DWORD)
+            });
+            if owner.is_null() {
+                continue;
+            }
+            let mut job = null_mut();
+            if DuplicateHandle(
+                owner,
+                h.HandleValue as HANDLE,
+                GetCurrentProcess(),
+                &mut job,
+                0,
+                FALSE,
+                DUPLICATE_SAME_ACCESS,
+            ) == FALSE
+            {
+                // Another process might have a handle to this job that can be duplicated.
+                continue;
+            }
+            if !h.Object.is_null() {
+                objects.insert(h.Object);
+            }
+            let mut in_job = FALSE;
+            if IsProcessInJob(process, job, &mut in_job) != FALSE && in_job != FALSE {
+                jobs.push(job);
+            } else {
+                CloseHandle(job);
+            }
+        }
+        for owner in owners.values() {
+            if !owner.is_null() {
+                CloseHandle(*owner);
+            }
+        }
+    }
+    jobs
+}
+
+/// Returns the processes (other than `target` and the current one) which have a handle opened on
+/// the process `target`.
+pub(crate) fn get_processes_with_handle_to(target: Pid) -> Vec<Pid> {
+    let process_type = match get_object_type_index("Process") {
+        Some(process_type) => process_type,
+        None => return Vec::new(),
+    };
+    let current_pid = unsafe { GetCurrentProcessId() } as usize;
+    let mut pids = Vec::new();
+
+    unsafe {
+        // Opening the target adds a handle to our process which gives its object address.
+        let target_handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, FALSE, target.0 as _);
+        if target_handle.is_null() {
+            return pids;
+        }
+        let handles = match get_system_handles() {
+            Some(handles) => handles,
+            None => {
+                CloseHandle(target_handle);
+                return pids;
+            }
+        };
+        let target_object = handles
+            .iter()
+            .find(|h| h.UniqueProcessId == current_pid && h.HandleValue == target_handle as usize)
+            .map(|h| h.Object)
+            .filter(|object| !object.is_null());
+        let mut owners: HashMap<usize, HANDLE> = HashMap::new();
+
+        for h in handles.iter().filter(|h| {
+            h.ObjectTypeIndex == process_type
+                && h.UniqueProcessId != current_pid
+                && h.UniqueProcessId != target.0
+        }) {
+            let pid = Pid(h.UniqueProcessId);
+            if pids.contains(&pid) {
+                continue;
+            }
+            let matches = match target_object {
+                Some(target_object) => h.Object == target_object,
+                // The object addresses are only available with enough privileges, otherwise
+                // the handles need to be duplicated to be compared.
+                None => {
+                    let owner = *owners.entry(h.UniqueProcessId).or_insert_with(|| {
+                        OpenProcess(PROCESS_DUP_HANDLE, FALSE, h.UniqueProcessId as DWORD)
+                    });
+                    if owner.is_null() {
+                        continue;
+                    }
+                    let mut process = null_mut();
+                    if DuplicateHandle(
+                        owner,
+                        h.HandleValue as HANDLE,
+                        GetCurrentProcess(),
+                        &mut process,
+                        PROCESS_QUERY_LIMITED_INFORMATION,
+                        FALSE,
+                        0,
+                    ) == FALSE
+                    {
+                        continue;
+                    }
+                    let matches = GetProcessId(process) as usize == target.0;
+                    CloseHandle(process);
+                    matches
+                }
+            };
+            if matches {
+                pids.push(pid);
+            }
+        }
+        for owner in owners.values() {
+            if !owner.is_null() {
+                CloseHandle(*owner);
+            }
+        }
+        CloseHandle(target_handle);
+    }
+    pids.sort_unstable();
+    pids
+}
@@ -2,15 +2,23 @@
 
 mod component;
 mod disk;
+#[cfg(feature = "async")]
+mod exit;
+mod full_process_info;
+mod handles;
 #[macro_use]
 mod macros;
 mod network;
-mod process;
+pub(crate) mod process;
 mod processor;
+mod services;
+mod signature;
 mod system;
+pub(crate) mod tcp_stats;
 mod tools;
 mod users;
 mod utils;
+mod window;
 
 pub use self::component::Component;
 pub use self::disk::Disk;
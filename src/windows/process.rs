@@ -1,9 +1,36 @@
 // Take a look at the license at the top of the repository in the LICENSE file.
 
-use crate::{DiskUsage, Pid, ProcessExt, ProcessStatus, Signal};
+use crate::sys::full_process_info::{
+    get_process_extension, get_process_threads, get_server_silo_ids,
+};
+use crate::sys::handles::{
+    get_handle_name, get_object_type_index, get_object_type_name, get_process_handles,
+    get_process_jobs,
+};
+use crate::sys::services::get_services;
+use crate::sys::signature::is_file_signed;
+use crate::sys::system::{get_reg_value_u32, SignalKind};
+use crate::sys::tcp_stats::{get_tcp_connections_usage, ConnectionId, ConnectionUsage};
+use crate::sys::users::{
+    get_process_integrity_level, get_process_user, get_token_user, is_process_elevated,
+};
+use crate::sys::utils::device_path_to_dos_path;
+use crate::sys::window::{
+    close_process_windows, get_console_window, get_foreground_process, get_process_windows,
+    is_attached_to_console,
+};
+use crate::{
+    DiskUsage, DllSearchMode, HandleInfo, IntegrityLevel, JobAccounting, MachineType,
+    MemoryPressure, ModuleInfo, NetworkUsage, Pid, Priority, ProcessArch, ProcessExt,
+    ProcessStatus, ServiceState, Signal, SigningLevel, Uid,
+};
 
+use std::any::Any;
+use std::collections::HashMap;
 use std::ffi::OsString;
 use std::fmt;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
 use std::mem::{size_of, zeroed, MaybeUninit};
 use std::ops::Deref;
 use std::os::windows::ffi::OsStringExt;
@@ -12,6 +39,8 @@ use std::path::{Path, PathBuf};
 use std::process;
 use std::ptr::null_mut;
 use std::str;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use libc::{c_void, memcpy};
 
@@ -19,29 +48,97 @@ use ntapi::ntpebteb::PEB;
 use ntapi::ntwow64::{PEB32, PRTL_USER_PROCESS_PARAMETERS32, RTL_USER_PROCESS_PARAMETERS32};
 use once_cell::sync::Lazy;
 
+use crate::sys::tools::NB_PROCESSORS;
+use ntapi::ntkeapi::{Suspended, Waiting, WrSuspended};
 use ntapi::ntpsapi::{
-    NtQueryInformationProcess, ProcessBasicInformation, ProcessCommandLineInformation,
-    ProcessWow64Information, PROCESSINFOCLASS, PROCESS_BASIC_INFORMATION,
+    NtQueryInformationProcess, NtResumeProcess, NtSetInformationProcess, NtSuspendProcess,
+    ProcessActivityThrottleState, ProcessBasicInformation, ProcessCommandLineInformation,
+    ProcessConsoleHostProcess, ProcessMitigationPolicy, ProcessProtectionInformation,
+    ProcessWow64Information, PsProtectedSignerAntimalware, PsProtectedSignerAuthenticode,
+    PsProtectedSignerCodeGen, PsProtectedSignerLsa, PsProtectedSignerWinSystem,
+    PsProtectedSignerWinTcb, PsProtectedSignerWindows, PsProtectedTypeNone, PROCESSINFOCLASS,
+    PROCESS_BASIC_INFORMATION, PROCESS_MITIGATION_POLICY_INFORMATION, PS_PROTECTION,
+};
+use ntapi::ntrtl::{
+    RtlCreateQueryDebugBuffer, RtlDestroyQueryDebugBuffer, RtlGetVersion,
+    RtlQueryProcessDebugInformation, PRTL_USER_PROCESS_PARAMETERS, RTL_QUERY_PROCESS_HEAP_SUMMARY,
+    RTL_USER_PROCESS_PARAMETERS,
 };
-use ntapi::ntrtl::{RtlGetVersion, PRTL_USER_PROCESS_PARAMETERS, RTL_USER_PROCESS_PARAMETERS};
-use winapi::shared::basetsd::SIZE_T;
-use winapi::shared::minwindef::{DWORD, FALSE, FILETIME, LPVOID, MAX_PATH, TRUE, ULONG};
-use winapi::shared::ntdef::{NT_SUCCESS, UNICODE_STRING};
+use winapi::shared::basetsd::{DWORD_PTR, SIZE_T, UINT32};
+use winapi::shared::minwindef::{
+    BOOL, DWORD, FALSE, FARPROC, FILETIME, HMODULE, LPVOID, MAX_PATH, TRUE, ULONG, WORD,
+};
+use winapi::shared::ntdef::{HRESULT, LONG, NTSTATUS, NT_SUCCESS, PWSTR, UNICODE_STRING};
 use winapi::shared::ntstatus::{
-    STATUS_BUFFER_OVERFLOW, STATUS_BUFFER_TOO_SMALL, STATUS_INFO_LENGTH_MISMATCH,
+    STATUS_ACCESS_DENIED, STATUS_BUFFER_OVERFLOW, STATUS_BUFFER_TOO_SMALL,
+    STATUS_INFO_LENGTH_MISMATCH,
+};
+use winapi::shared::sddl::ConvertSidToStringSidW;
+use winapi::shared::winerror::{
+    ERROR_ACCESS_DENIED, ERROR_INSUFFICIENT_BUFFER, ERROR_INVALID_PARAMETER, ERROR_NO_TOKEN,
+    ERROR_SUCCESS,
 };
+use winapi::um::accctrl::SE_KERNEL_OBJECT;
+use winapi::um::aclapi::GetSecurityInfo;
+use winapi::um::errhandlingapi::{GetLastError, SetLastError};
 use winapi::um::handleapi::CloseHandle;
-use winapi::um::memoryapi::{ReadProcessMemory, VirtualQueryEx};
-use winapi::um::processthreadsapi::{GetProcessTimes, GetSystemTimes, OpenProcess};
+use winapi::um::jobapi2::QueryInformationJobObject;
+use winapi::um::libloaderapi::{
+    GetModuleHandleW, GetProcAddress, LoadLibraryExW, LOAD_LIBRARY_SEARCH_SYSTEM32,
+};
+use winapi::um::memoryapi::{GetProcessWorkingSetSizeEx, ReadProcessMemory, VirtualQueryEx};
+use winapi::um::minwinbase::STILL_ACTIVE;
+use winapi::um::processthreadsapi::{
+    GetCurrentProcess, GetCurrentProcessId, GetExitCodeProcess, GetPriorityClass,
+    GetProcessHandleCount, GetProcessTimes, GetSystemTimes, GetThreadPriority, OpenProcess,
+    OpenThread, OpenThreadToken, ProcessIdToSessionId, SetPriorityClass, TerminateProcess,
+};
 use winapi::um::psapi::{
-    EnumProcessModulesEx, GetModuleBaseNameW, GetModuleFileNameExW, GetProcessMemoryInfo,
-    LIST_MODULES_ALL, PROCESS_MEMORY_COUNTERS, PROCESS_MEMORY_COUNTERS_EX,
+    EnumProcessModulesEx, GetMappedFileNameW, GetModuleBaseNameW, GetModuleFileNameExW,
+    GetModuleInformation, GetProcessMemoryInfo, QueryWorkingSet, LIST_MODULES_ALL, MODULEINFO,
+    PROCESS_MEMORY_COUNTERS, PROCESS_MEMORY_COUNTERS_EX, PSAPI_WORKING_SET_BLOCK,
+};
+use winapi::um::synchapi::WaitForSingleObject;
+use winapi::um::sysinfoapi::{
+    GetNativeSystemInfo, GetSystemInfo, GlobalMemoryStatusEx, MEMORYSTATUSEX, SYSTEM_INFO,
+};
+use winapi::um::winbase::{
+    GetProcessAffinityMask, GetProcessIoCounters, LocalFree, QueryFullProcessImageNameW,
+    ABOVE_NORMAL_PRIORITY_CLASS, BELOW_NORMAL_PRIORITY_CLASS, CREATE_NO_WINDOW,
+    HIGH_PRIORITY_CLASS, IDLE_PRIORITY_CLASS, INFINITE, NORMAL_PRIORITY_CLASS,
+    REALTIME_PRIORITY_CLASS, THREAD_PRIORITY_ERROR_RETURN, WAIT_OBJECT_0,
 };
-use winapi::um::winbase::{GetProcessIoCounters, CREATE_NO_WINDOW};
+use winapi::um::wincon::{GenerateConsoleCtrlEvent, CTRL_BREAK_EVENT};
 use winapi::um::winnt::{
-    HANDLE, IO_COUNTERS, MEMORY_BASIC_INFORMATION, PROCESS_QUERY_INFORMATION, PROCESS_VM_READ,
-    RTL_OSVERSIONINFOEXW, ULARGE_INTEGER,
+    JobObjectBasicAccountingInformation, JobObjectExtendedLimitInformation, ProcessImageLoadPolicy,
+    ProcessSignaturePolicy, HANDLE, IMAGE_DIRECTORY_ENTRY_COM_DESCRIPTOR, IMAGE_DOS_HEADER,
+    IMAGE_DOS_SIGNATURE, IMAGE_FILE_HEADER, IMAGE_FILE_MACHINE_AMD64, IMAGE_FILE_MACHINE_ARM64,
+    IMAGE_FILE_MACHINE_ARMNT, IMAGE_FILE_MACHINE_I386, IMAGE_NT_OPTIONAL_HDR32_MAGIC,
+    IMAGE_NT_OPTIONAL_HDR64_MAGIC, IMAGE_NT_SIGNATURE, IMAGE_OPTIONAL_HEADER32,
+    IMAGE_OPTIONAL_HEADER64, IMAGE_SUBSYSTEM_WINDOWS_CUI, IO_COUNTERS,
+    JOBOBJECT_BASIC_ACCOUNTING_INFORMATION, JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+    JOB_OBJECT_LIMIT_PROCESS_MEMORY, LPWSTR, MEMORY_BASIC_INFORMATION, MEM_IMAGE,
+    OWNER_SECURITY_INFORMATION, PROCESSOR_ARCHITECTURE_AMD64, PROCESSOR_ARCHITECTURE_ARM64,
+    PROCESSOR_ARCHITECTURE_INTEL, PROCESS_DUP_HANDLE, PROCESS_MITIGATION_IMAGE_LOAD_POLICY,
+    PROCESS_MITIGATION_POLICY, PROCESS_QUERY_INFORMATION, PROCESS_QUERY_LIMITED_INFORMATION,
+    PROCESS_SET_INFORMATION, PROCESS_SUSPEND_RESUME, PROCESS_TERMINATE, PROCESS_VM_READ,
+    PSECURITY_DESCRIPTOR, PSID, READ_CONTROL, RTL_OSVERSIONINFOEXW, SYNCHRONIZE,
+    THREAD_QUERY_INFORMATION, TOKEN_QUERY, ULARGE_INTEGER,
 };
+use winapi::um::winreg::HKEY_LOCAL_MACHINE;
+use winapi::um::winsvc::SERVICE_ACTIVE;
+use winapi::um::winuser::{IsIconic, IsWindowVisible};
+
+// The modules implementing the CLR, depending on the .NET version.
+const CLR_MODULES: &[&str] = &["clr.dll", "coreclr.dll", "mscorwks.dll"];
+
+// Number of page faults since the previous refresh above which the memory pressure of a process
+// is considered as medium or high.
+const MEDIUM_PRESSURE_PAGE_FAULTS: u64 = 1_000;
+const HIGH_PRESSURE_PAGE_FAULTS: u64 = 10_000;
+
+// CPU usage variations (in %) below this value don't make a process "dirty".
+const CPU_USAGE_DIRTY_THRESHOLD: f32 = 0.1;
 
 impl fmt::Display for ProcessStatus {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -52,18 +149,33 @@ impl fmt::Display for ProcessStatus {
     }
 }
 
+// Reading the memory of the process (for its command line or its environment) requires
+// `PROCESS_VM_READ`, so a handle with these rights is tried first. Otherwise, a handle with
+// `PROCESS_QUERY_LIMITED_INFORMATION` is used: it's enough to get most of the information (name,
+// times, memory...) and is granted far more often (for processes of other users or protected ones
+// notably).
+//
+// The returned handle must either be closed or stored in a `Process`, which closes it when
+// dropped.
 fn get_process_handler(pid: Pid) -> Option<HANDLE> {
     if pid.0 == 0 {
         return None;
     }
-    let options = PROCESS_QUERY_INFORMATION | PROCESS_VM_READ;
 
     unsafe {
-        let process_handler = OpenProcess(options, FALSE, pid.0 as DWORD);
-        if process_handler.is_null() {
+        let process_handler = OpenProcess(
+            PROCESS_QUERY_INFORMATION | PROCESS_VM_READ,
+            FALSE,
+            pid.0 as DWORD,
+        );
+        if !process_handler.is_null() {
+            return Some(process_handler);
+        }
+        let limited_handler = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, FALSE, pid.0 as DWORD);
+        if limited_handler.is_null() {
             None
         } else {
-            Some(process_handler)
+            Some(limited_handler)
         }
     }
 }
@@ -92,20 +204,62 @@ pub struct Process {
     environ: Vec<String>,
     cwd: PathBuf,
     root: PathBuf,
+    // The working set and pagefile sizes are kept in bytes.
     pub(crate) memory: u64,
+    pub(crate) old_memory: u64,
+    pub(crate) peak_memory: u64,
+    pub(crate) pagefile_usage: u64,
+    pub(crate) page_faults: u32,
+    pub(crate) old_page_faults: u32,
     pub(crate) virtual_memory: u64,
     parent: Option<Pid>,
     status: ProcessStatus,
     handle: PtrWrapper<HANDLE>,
     cpu_calc_values: CPUsageCalculationValues,
+    // The CPU times are kept in 100-nanosecond units.
+    kernel_time: u64,
+    user_time: u64,
     start_time: u64,
+    start_time_nanos: u64,
     pub(crate) run_time: u64,
     cpu_usage: f32,
+    cpu_usage_machine_share: f32,
     pub(crate) updated: bool,
     old_read_bytes: u64,
     old_written_bytes: u64,
     read_bytes: u64,
     written_bytes: u64,
+    old_read_operations: u64,
+    old_written_operations: u64,
+    read_operations: u64,
+    written_operations: u64,
+    pub(crate) thread_count: usize,
+    user_id: Option<Uid>,
+    user_name: Option<String>,
+    elevated: Option<bool>,
+    integrity_level: Option<IntegrityLevel>,
+    handle_count: u32,
+    session_id: Option<u32>,
+    arch: Option<ProcessArch>,
+    // Why the parameters couldn't be read. If it won't change by trying again, they're only read
+    // again if requested.
+    params_error: Option<ProcessParamError>,
+    modules: Vec<ModuleInfo>,
+    pub(crate) ready_time: Option<Duration>,
+    user_data: Option<Box<dyn Any + Send + Sync>>,
+    cpu_history: Vec<f32>,
+    dirty: bool,
+    // `false` until `update` was called once, so a new process is always dirty.
+    first_update_done: bool,
+    io_counters_read: bool,
+    last_io_time: Option<u64>,
+    network_read: bool,
+    old_network_rx_bytes: u64,
+    old_network_tx_bytes: u64,
+    network_rx_bytes: u64,
+    network_tx_bytes: u64,
+    // The number of bytes received and sent on each TCP connection during the last refresh.
+    tcp_connections: HashMap<ConnectionId, (u64, u64)>,
 }
 
 struct CPUsageCalculationValues {
@@ -124,6 +278,17 @@ impl CPUsageCalculationValues {
             old_system_user_cpu: 0,
         }
     }
+
+    // Uses the current times as baseline, so the first computed usage only covers the time
+    // since then instead of the whole life of the process.
+    fn with_times(process_sys: u64, process_user: u64, system_times: &SystemTimes) -> Self {
+        CPUsageCalculationValues {
+            old_process_sys_cpu: process_sys,
+            old_process_user_cpu: process_user,
+            old_system_sys_cpu: system_times.kernel,
+            old_system_user_cpu: system_times.user,
+        }
+    }
 }
 static WINDOWS_8_1_OR_NEWER: Lazy<bool> = Lazy::new(|| unsafe {
     let mut version_info: RTL_OSVERSIONINFOEXW = MaybeUninit::zeroed().assume_init();
@@ -140,15 +305,28 @@ static WINDOWS_8_1_OR_NEWER: Lazy<bool> = Lazy::new(|| unsafe {
         || version_info.dwMajorVersion == 6 && version_info.dwMinorVersion >= 3
 });
 
-unsafe fn get_process_name(process_handler: HANDLE, h_mod: *mut c_void) -> String {
-    let mut process_name = [0u16; MAX_PATH + 1];
+// Longest path supported by Windows when using the `\\?\` prefix, in wide chars.
+const MAX_EXTENDED_PATH: usize = 32_768;
 
-    GetModuleBaseNameW(
-        process_handler,
-        h_mod as _,
-        process_name.as_mut_ptr(),
-        MAX_PATH as DWORD + 1,
-    );
+// Calls `f` with a growing buffer until the string fits: the module functions silently truncate
+// it and, depending on the Windows version, return the buffer capacity or the capacity minus the
+// null terminator.
+unsafe fn get_module_string<F: FnMut(*mut u16, DWORD) -> DWORD>(mut f: F) -> Vec<u16> {
+    let mut buffer = vec![0u16; MAX_PATH + 1];
+    loop {
+        let len = f(buffer.as_mut_ptr(), buffer.len() as DWORD) as usize;
+        if len + 1 < buffer.len() || buffer.len() >= MAX_EXTENDED_PATH {
+            buffer.truncate(len);
+            return buffer;
+        }
+        buffer.resize(buffer.len() * 2, 0);
+    }
+}
+
+unsafe fn get_process_name(process_handler: HANDLE, h_mod: *mut c_void) -> String {
+    let process_name = get_module_string(|buffer, size| {
+        GetModuleBaseNameW(process_handler, h_mod as _, buffer, size)
+    });
     null_terminated_wchar_to_string(&process_name)
 }
 
@@ -163,205 +341,3483 @@ unsafe fn get_h_mod(process_handler: HANDLE, h_mod: &mut *mut c_void) -> bool {
     ) != 0
 }
 
-unsafe fn get_exe(process_handler: HANDLE, h_mod: *mut c_void) -> PathBuf {
-    let mut exe_buf = [0u16; MAX_PATH + 1];
-    GetModuleFileNameExW(
-        process_handler,
-        h_mod as _,
-        exe_buf.as_mut_ptr(),
-        MAX_PATH as DWORD + 1,
-    );
+unsafe fn get_module_path(process_handler: HANDLE, h_mod: *mut c_void) -> PathBuf {
+    let exe_buf = get_module_string(|buffer, size| {
+        GetModuleFileNameExW(process_handler, h_mod as _, buffer, size)
+    });
 
     PathBuf::from(null_terminated_wchar_to_string(&exe_buf))
 }
 
-impl Process {
-    pub(crate) fn new_from_pid(pid: Pid, now: u64) -> Option<Process> {
-        unsafe {
-            let process_handler = OpenProcess(PROCESS_QUERY_INFORMATION, FALSE, pid.0 as _);
-            if process_handler.is_null() {
-                return None;
-            }
-            let mut info: MaybeUninit<PROCESS_BASIC_INFORMATION> = MaybeUninit::uninit();
-            if NtQueryInformationProcess(
+// `QueryFullProcessImageNameW` is tried first: unlike enumerating the modules, it works for
+// protected processes and for processes of another bitness, and it's much cheaper. The modules
+// are only enumerated if `enum_modules` is `true`.
+unsafe fn get_exe(process_handler: HANDLE, enum_modules: bool) -> PathBuf {
+    if let Some(exe) = get_full_image_name(process_handler) {
+        return exe;
+    }
+    if !enum_modules {
+        return PathBuf::new();
+    }
+    let mut h_mod = null_mut();
+    if !get_h_mod(process_handler, &mut h_mod) {
+        return PathBuf::new();
+    }
+    get_module_path(process_handler, h_mod)
+}
+
+unsafe fn get_full_image_name(process_handler: HANDLE) -> Option<PathBuf> {
+    let mut exe_buf = vec![0u16; MAX_PATH + 1];
+    loop {
+        let mut size = exe_buf.len() as DWORD;
+        if QueryFullProcessImageNameW(process_handler, 0, exe_buf.as_mut_ptr(), &mut size) != FALSE
+        {
+            return Some(PathBuf::from(OsString::from_wide(
+                &exe_buf[..size as usize],
+            )));
+        }
+        // Unlike the module functions, this one fails if the buffer is too small.
+        if std::io::Error::last_os_error().raw_os_error() != Some(ERROR_INSUFFICIENT_BUFFER as _)
+            || exe_buf.len() >= MAX_EXTENDED_PATH
+        {
+            return None;
+        }
+        exe_buf.resize(exe_buf.len() * 2, 0);
+    }
+}
+
+unsafe fn get_image_path_from_params(process_handler: HANDLE) -> Option<PathBuf> {
+    let pwow32info = get_wow64_peb(process_handler).ok()?;
+    let params = read_process_params(process_handler, pwow32info).ok()?;
+    let buffer = params.get_image_path(process_handler).ok()?;
+    Some(PathBuf::from(null_terminated_wchar_to_string(&buffer)))
+}
+
+unsafe fn read_process_memory<T>(process_handler: HANDLE, address: usize) -> Option<T> {
+    let mut value = MaybeUninit::<T>::uninit();
+    let mut nb_read = 0;
+    if ReadProcessMemory(
+        process_handler,
+        address as _,
+        value.as_mut_ptr() as _,
+        size_of::<T>() as SIZE_T,
+        &mut nb_read,
+    ) == FALSE
+        || nb_read != size_of::<T>() as SIZE_T
+    {
+        return None;
+    }
+    Some(value.assume_init())
+}
+
+// Returns the address of the `IMAGE_NT_HEADERS` of the image loaded at `base`.
+unsafe fn get_nt_headers_address(process_handler: HANDLE, base: usize) -> Option<usize> {
+    let dos_header: IMAGE_DOS_HEADER = read_process_memory(process_handler, base)?;
+    if dos_header.e_magic != IMAGE_DOS_SIGNATURE || dos_header.e_lfanew <= 0 {
+        return None;
+    }
+    let nt_headers = base + dos_header.e_lfanew as usize;
+    let signature: DWORD = read_process_memory(process_handler, nt_headers)?;
+    if signature != IMAGE_NT_SIGNATURE {
+        return None;
+    }
+    Some(nt_headers)
+}
+
+// Returns the address of the `IMAGE_NT_HEADERS` of the main image of the process.
+unsafe fn get_main_nt_headers_address(process_handler: HANDLE) -> Option<usize> {
+    let mut h_mod = null_mut();
+    if !get_h_mod(process_handler, &mut h_mod) || h_mod.is_null() {
+        return None;
+    }
+    get_nt_headers_address(process_handler, h_mod as usize)
+}
+
+unsafe fn get_image_file_header(process_handler: HANDLE) -> Option<IMAGE_FILE_HEADER> {
+    let nt_headers = get_main_nt_headers_address(process_handler)?;
+    // `IMAGE_FILE_HEADER` comes right after the signature.
+    read_process_memory(process_handler, nt_headers + size_of::<DWORD>())
+}
+
+unsafe fn get_image_checksum(process_handler: HANDLE) -> Option<u32> {
+    let nt_headers = get_main_nt_headers_address(process_handler)?;
+    // The optional header comes right after `IMAGE_FILE_HEADER` and its layout depends on its
+    // magic number.
+    let optional_header = nt_headers + size_of::<DWORD>() + size_of::<IMAGE_FILE_HEADER>();
+    let magic: WORD = read_process_memory(process_handler, optional_header)?;
+    match magic {
+        IMAGE_NT_OPTIONAL_HDR64_MAGIC => {
+            read_process_memory::<IMAGE_OPTIONAL_HEADER64>(process_handler, optional_header)
+                .map(|header| header.CheckSum)
+        }
+        IMAGE_NT_OPTIONAL_HDR32_MAGIC => {
+            read_process_memory::<IMAGE_OPTIONAL_HEADER32>(process_handler, optional_header)
+                .map(|header| header.CheckSum)
+        }
+        _ => None,
+    }
+}
+
+// Returns the modules loaded by the process, the main image being the first one.
+unsafe fn get_modules(process_handler: HANDLE) -> Vec<HMODULE> {
+    let mut modules: Vec<HMODULE> = vec![null_mut(); 256];
+
+    loop {
+        let mut cb_needed = 0;
+        if EnumProcessModulesEx(
+            process_handler,
+            modules.as_mut_ptr(),
+            (modules.len() * size_of::<HMODULE>()) as DWORD,
+            &mut cb_needed,
+            LIST_MODULES_ALL,
+        ) == FALSE
+        {
+            return Vec::new();
+        }
+        let nb_modules = cb_needed as usize / size_of::<HMODULE>();
+        if nb_modules <= modules.len() {
+            modules.truncate(nb_modules);
+            return modules;
+        }
+        // Modules might have been loaded in the meantime.
+        modules.resize(nb_modules, null_mut());
+    }
+}
+
+// Returns the modules loaded by the process with their paths and where they're loaded, the main
+// image being the first one. Modules unloaded in the meantime are skipped.
+unsafe fn get_module_infos(process_handler: HANDLE) -> Vec<ModuleInfo> {
+    get_modules(process_handler)
+        .into_iter()
+        .filter_map(|h_mod| {
+            let mut info: MODULEINFO = zeroed();
+            if GetModuleInformation(
                 process_handler,
-                ProcessBasicInformation,
-                info.as_mut_ptr() as *mut _,
-                size_of::<PROCESS_BASIC_INFORMATION>() as _,
-                null_mut(),
-            ) != 0
+                h_mod,
+                &mut info,
+                size_of::<MODULEINFO>() as DWORD,
+            ) == FALSE
             {
-                CloseHandle(process_handler);
                 return None;
             }
-            let info = info.assume_init();
-            Some(Process::new_with_handle(
-                pid,
-                if info.InheritedFromUniqueProcessId as usize != 0 {
-                    Some(Pid(info.InheritedFromUniqueProcessId as _))
-                } else {
-                    None
-                },
+            Some(ModuleInfo {
+                path: get_module_path(process_handler, h_mod as _),
+                base_address: info.lpBaseOfDll as usize,
+                size: info.SizeOfImage as usize,
+            })
+        })
+        .collect()
+}
+
+// Returns the base address of every image mapped in the process memory, including the ones
+// which weren't mapped by the loader and so aren't listed in its modules.
+unsafe fn get_mapped_images(process_handler: HANDLE) -> Vec<usize> {
+    let mut images = Vec::new();
+    let mut address: usize = 0;
+    let mut info: MEMORY_BASIC_INFORMATION = zeroed();
+
+    while VirtualQueryEx(
+        process_handler,
+        address as _,
+        &mut info,
+        size_of::<MEMORY_BASIC_INFORMATION>(),
+    ) != 0
+    {
+        if info.Type == MEM_IMAGE && info.BaseAddress == info.AllocationBase {
+            images.push(info.BaseAddress as usize);
+        }
+        match (info.BaseAddress as usize).checked_add(info.RegionSize) {
+            Some(next) if next > address => address = next,
+            _ => break,
+        }
+    }
+    images
+}
+
+// Returns `true` if the image loaded at `base` has a CLR header, meaning it's a .NET assembly.
+unsafe fn is_managed_image(process_handler: HANDLE, base: usize) -> bool {
+    let nt_headers = match get_nt_headers_address(process_handler, base) {
+        Some(nt_headers) => nt_headers,
+        None => return false,
+    };
+    let optional_header = nt_headers + size_of::<DWORD>() + size_of::<IMAGE_FILE_HEADER>();
+    let directory = match read_process_memory::<WORD>(process_handler, optional_header) {
+        Some(IMAGE_NT_OPTIONAL_HDR64_MAGIC) => {
+            read_process_memory::<IMAGE_OPTIONAL_HEADER64>(process_handler, optional_header)
+                .map(|header| header.DataDirectory[IMAGE_DIRECTORY_ENTRY_COM_DESCRIPTOR as usize])
+        }
+        Some(IMAGE_NT_OPTIONAL_HDR32_MAGIC) => {
+            read_process_memory::<IMAGE_OPTIONAL_HEADER32>(process_handler, optional_header)
+                .map(|header| header.DataDirectory[IMAGE_DIRECTORY_ENTRY_COM_DESCRIPTOR as usize])
+        }
+        _ => None,
+    };
+    matches!(directory, Some(directory) if directory.VirtualAddress != 0 && directory.Size != 0)
+}
+
+unsafe fn get_mapped_file_name(process_handler: HANDLE, base: usize) -> Option<String> {
+    let mut buffer = [0u16; MAX_PATH + 1];
+    let len = GetMappedFileNameW(
+        process_handler,
+        base as _,
+        buffer.as_mut_ptr(),
+        buffer.len() as DWORD,
+    );
+    if len == 0 {
+        return None;
+    }
+    Some(device_path_to_dos_path(&String::from_utf16_lossy(
+        &buffer[..len as usize],
+    )))
+}
+
+// Returns the preferred base address written in the headers of the image file at `path`. It can't
+// be read from the process memory because the loader overwrites it with the actual base address.
+fn get_preferred_image_base(path: &Path) -> Option<u64> {
+    fn read_at<const N: usize>(file: &mut File, offset: u64) -> Option<[u8; N]> {
+        let mut buffer = [0; N];
+        file.seek(SeekFrom::Start(offset)).ok()?;
+        file.read_exact(&mut buffer).ok()?;
+        Some(buffer)
+    }
+
+    let mut file = File::open(path).ok()?;
+    if u16::from_le_bytes(read_at(&mut file, 0)?) != IMAGE_DOS_SIGNATURE {
+        return None;
+    }
+    // Offset of `e_lfanew` in `IMAGE_DOS_HEADER`.
+    let nt_headers = u32::from_le_bytes(read_at(&mut file, 0x3c)?) as u64;
+    if u32::from_le_bytes(read_at(&mut file, nt_headers)?) != IMAGE_NT_SIGNATURE {
+        return None;
+    }
+    let optional_header =
+        nt_headers + size_of::<DWORD>() as u64 + size_of::<IMAGE_FILE_HEADER>() as u64;
+    // `ImageBase` is a 64-bit value at offset 24 in `IMAGE_OPTIONAL_HEADER64` and a 32-bit value
+    // at offset 28 in `IMAGE_OPTIONAL_HEADER32`.
+    match u16::from_le_bytes(read_at(&mut file, optional_header)?) {
+        IMAGE_NT_OPTIONAL_HDR64_MAGIC => Some(u64::from_le_bytes(read_at(
+            &mut file,
+            optional_header + 24,
+        )?)),
+        IMAGE_NT_OPTIONAL_HDR32_MAGIC => {
+            Some(u32::from_le_bytes(read_at(&mut file, optional_header + 28)?) as u64)
+        }
+        _ => None,
+    }
+}
+
+unsafe fn get_image_subsystem(process_handler: HANDLE) -> Option<WORD> {
+    let nt_headers = get_main_nt_headers_address(process_handler)?;
+    let optional_header = nt_headers + size_of::<DWORD>() + size_of::<IMAGE_FILE_HEADER>();
+    let magic: WORD = read_process_memory(process_handler, optional_header)?;
+    match magic {
+        IMAGE_NT_OPTIONAL_HDR64_MAGIC => {
+            read_process_memory::<IMAGE_OPTIONAL_HEADER64>(process_handler, optional_header)
+                .map(|header| header.Subsystem)
+        }
+        IMAGE_NT_OPTIONAL_HDR32_MAGIC => {
+            read_process_memory::<IMAGE_OPTIONAL_HEADER32>(process_handler, optional_header)
+                .map(|header| header.Subsystem)
+        }
+        _ => None,
+    }
+}
+
+// Returns the native PEB of the process.
+unsafe fn read_peb(process_handler: HANDLE) -> Option<PEB> {
+    let mut info: PROCESS_BASIC_INFORMATION = zeroed();
+    if !NT_SUCCESS(NtQueryInformationProcess(
+        process_handler,
+        ProcessBasicInformation,
+        &mut info as *mut _ as _,
+        size_of::<PROCESS_BASIC_INFORMATION>() as _,
+        null_mut(),
+    )) {
+        return None;
+    }
+    read_process_memory(process_handler, info.PebBaseAddress as usize)
+}
+
+// Returns the address of the default heap of the process, as stored in its PEB.
+unsafe fn get_process_heap_address(process_handler: HANDLE) -> Option<usize> {
+    Some(read_peb(process_handler)?.ProcessHeap as usize)
+}
+
+// Returns the number of bytes allocated and committed in the default heap of the process.
+unsafe fn get_default_heap_usage(pid: Pid, process_handler: HANDLE) -> Option<(usize, usize)> {
+    let heap_address = get_process_heap_address(process_handler);
+    let buffer = RtlCreateQueryDebugBuffer(0, FALSE as _);
+    if buffer.is_null() {
+        return None;
+    }
+    // For another process, a thread would be created in it to gather the information.
+    let status =
+        RtlQueryProcessDebugInformation(pid.0 as _, RTL_QUERY_PROCESS_HEAP_SUMMARY, buffer);
+    let mut usage = None;
+    if !NT_SUCCESS(status) {
+        sysinfo_debug!("RtlQueryProcessDebugInformation failed: {}", status);
+    } else if !(*buffer).Heaps.is_null() {
+        let heaps = &*(*buffer).Heaps;
+        let heaps = std::slice::from_raw_parts(heaps.Heaps.as_ptr(), heaps.NumberOfHeaps as usize);
+        // The default heap is normally the first one.
+        usage = heaps
+            .iter()
+            .find(|heap| Some(heap.BaseAddress as usize) == heap_address)
+            .or_else(|| heaps.first())
+            .map(|heap| (heap.BytesAllocated, heap.BytesCommitted));
+    }
+    RtlDestroyQueryDebugBuffer(buffer);
+    usage
+}
+
+// Returns the PID of the console host (`conhost.exe` or `OpenConsole.exe`) of the process.
+unsafe fn get_console_host_pid(process_handler: HANDLE) -> Option<Pid> {
+    let mut host: usize = 0;
+    let status = NtQueryInformationProcess(
+        process_handler,
+        ProcessConsoleHostProcess,
+        &mut host as *mut _ as _,
+        size_of::<usize>() as _,
+        null_mut(),
+    );
+    if !NT_SUCCESS(status) {
+        return None;
+    }
+    // The lowest bits are used as flags.
+    let pid = host & !3;
+    if pid == 0 {
+        None
+    } else {
+        Some(Pid(pid))
+    }
+}
+
+// The beginning of `PEB_LDR_DATA`, with `P` being the pointer type of the process.
+#[repr(C)]
+struct PebLdrDataHeader<P> {
+    length: u32,
+    initialized: u8,
+    ss_handle: P,
+    in_load_order_module_list: [P; 2],
+}
+
+#[repr(C)]
+struct UnicodeStringHeader<P> {
+    length: u16,
+    maximum_length: u16,
+    buffer: P,
+}
+
+// The beginning of `LDR_DATA_TABLE_ENTRY`, which is the only part that didn't change across
+// Windows versions.
+#[repr(C)]
+struct LdrDataTableEntryHeader<P> {
+    in_load_order_links: [P; 2],
+    in_memory_order_links: [P; 2],
+    in_initialization_order_links: [P; 2],
+    dll_base: P,
+    entry_point: P,
+    size_of_image: u32,
+    full_dll_name: UnicodeStringHeader<P>,
+}
+
+// Upper bound on the number of entries read, in case the list is modified while being walked.
+const MAX_LOADER_ENTRIES: usize = 4096;
+
+// Walks the `InLoadOrderModuleList` of the loader data at `ldr`.
+unsafe fn get_loader_modules<P: Copy + Into<u64>>(
+    process_handler: HANDLE,
+    ldr: u64,
+) -> Vec<PathBuf> {
+    let mut modules = Vec::new();
+    let ldr_data: PebLdrDataHeader<P> = match read_process_memory(process_handler, ldr as usize) {
+        Some(ldr_data) => ldr_data,
+        None => return modules,
+    };
+    let head = ldr + (size_of::<PebLdrDataHeader<P>>() - size_of::<[P; 2]>()) as u64;
+    let mut current: u64 = ldr_data.in_load_order_module_list[0].into();
+
+    while current != head && current != 0 && modules.len() < MAX_LOADER_ENTRIES {
+        let entry: LdrDataTableEntryHeader<P> =
+            match read_process_memory(process_handler, current as usize) {
+                Some(entry) => entry,
+                None => {
+                    sysinfo_debug!("failed to read loader entry at {:#x}", current);
+                    break;
+                }
+            };
+        let mut name = vec![0u16; entry.full_dll_name.length as usize / 2];
+        let mut nb_read = 0;
+        if !name.is_empty()
+            && ReadProcessMemory(
                 process_handler,
-                now,
+                entry.full_dll_name.buffer.into() as usize as _,
+                name.as_mut_ptr() as _,
+                (name.len() * 2) as SIZE_T,
+                &mut nb_read,
+            ) != FALSE
+        {
+            name.truncate(nb_read / 2);
+            modules.push(PathBuf::from(OsString::from_wide(&name)));
+        }
+        current = entry.in_load_order_links[0].into();
+    }
+    modules
+}
+
+// Pointer type of the processes which have the same bitness as this one.
+#[cfg(target_pointer_width = "64")]
+type NativePtr = u64;
+#[cfg(not(target_pointer_width = "64"))]
+type NativePtr = u32;
+
+// Returns the paths of the modules of the process, in the order the loader loaded them.
+unsafe fn get_modules_in_load_order(process_handler: HANDLE) -> Vec<PathBuf> {
+    let wow64_peb = match get_wow64_peb(process_handler) {
+        Ok(wow64_peb) => wow64_peb,
+        Err(_) => return Vec::new(),
+    };
+    if !wow64_peb.is_null() {
+        // The 32-bit loader of a WOW64 process keeps its own list.
+        return match read_process_memory::<PEB32>(process_handler, wow64_peb as usize) {
+            Some(peb32) => get_loader_modules::<u32>(process_handler, peb32.Ldr as u64),
+            None => Vec::new(),
+        };
+    }
+    // On a 32-bit build, the target is a 64-bit process if this one runs in the WOW64 emulator,
+    // and its loader data can't be read then.
+    if !cfg!(target_pointer_width = "64")
+        && !matches!(get_wow64_peb(GetCurrentProcess()), Ok(peb) if peb.is_null())
+    {
+        return Vec::new();
+    }
+    match read_peb(process_handler) {
+        Some(peb) => get_loader_modules::<NativePtr>(process_handler, peb.Ldr as usize as u64),
+        None => Vec::new(),
+    }
+}
+
+// Kinds of objects counted by `GetGuiResources`.
+const GR_GDIOBJECTS: DWORD = 0;
+const GR_GDIOBJECTS_PEAK: DWORD = 2;
+
+// Not declared by `winapi`.
+#[link(name = "user32")]
+extern "system" {
+    fn GetGuiResources(hProcess: HANDLE, uiFlags: DWORD) -> DWORD;
+}
+
+// Returns the number of GUI objects of the given kind used by the process.
+fn get_gui_resources(process_handler: HANDLE, flags: DWORD) -> Option<u32> {
+    if process_handler.is_null() {
+        return None;
+    }
+    unsafe {
+        // `0` is a valid count too, so the last error is needed to tell if it failed.
+        SetLastError(0);
+        let count = GetGuiResources(process_handler, flags);
+        if count == 0 && GetLastError() != 0 {
+            sysinfo_debug!("GetGuiResources failed");
+            return None;
+        }
+        Some(count)
+    }
+}
+
+unsafe fn get_mitigation_policy(
+    process_handler: HANDLE,
+    policy: PROCESS_MITIGATION_POLICY,
+) -> Option<PROCESS_MITIGATION_POLICY_INFORMATION> {
+    let mut info: PROCESS_MITIGATION_POLICY_INFORMATION = zeroed();
+    info.Policy = policy;
+    let status = NtQueryInformationProcess(
+        process_handler,
+        ProcessMitigationPolicy,
+        &mut info as *mut _ as _,
+        size_of::<PROCESS_MITIGATION_POLICY_INFORMATION>() as _,
+        null_mut(),
+    );
+    if !NT_SUCCESS(status) {
+        sysinfo_debug!(
+            "NtQueryInformationProcess(ProcessMitigationPolicy) failed: {}",
+            status
+        );
+        return None;
+    }
+    Some(info)
+}
+
+unsafe fn get_image_load_policy(
+    process_handler: HANDLE,
+) -> Option<PROCESS_MITIGATION_IMAGE_LOAD_POLICY> {
+    get_mitigation_policy(process_handler, ProcessImageLoadPolicy)
+        .map(|info| info.u.ImageLoadPolicy)
+}
+
+// Returns the signing level required by the kernel for the images loaded by the process.
+#[allow(non_upper_case_globals)]
+unsafe fn get_signing_level(process_handler: HANDLE) -> Option<SigningLevel> {
+    let mut protection: PS_PROTECTION = zeroed();
+    let status = NtQueryInformationProcess(
+        process_handler,
+        ProcessProtectionInformation,
+        &mut protection as *mut _ as _,
+        size_of::<PS_PROTECTION>() as _,
+        null_mut(),
+    );
+    if !NT_SUCCESS(status) {
+        sysinfo_debug!(
+            "NtQueryInformationProcess(ProcessProtectionInformation) failed: {}",
+            status
+        );
+        return None;
+    }
+    // For protected processes, the required level depends on the signer of the process, the
+    // same way the kernel maps them.
+    if protection.Type() != PsProtectedTypeNone as u8 {
+        let level = match protection.Signer() as u32 {
+            PsProtectedSignerAuthenticode => SigningLevel::Authenticode,
+            PsProtectedSignerCodeGen => SigningLevel::DynamicCodegen,
+            PsProtectedSignerAntimalware => SigningLevel::Antimalware,
+            PsProtectedSignerLsa | PsProtectedSignerWindows => SigningLevel::Windows,
+            PsProtectedSignerWinTcb | PsProtectedSignerWinSystem => SigningLevel::WindowsTcb,
+            _ => SigningLevel::Unchecked,
+        };
+        return Some(level);
+    }
+    let policy = get_mitigation_policy(process_handler, ProcessSignaturePolicy)?
+        .u
+        .SignaturePolicy;
+    Some(if policy.MicrosoftSignedOnly() != 0 {
+        SigningLevel::Microsoft
+    } else if policy.StoreSignedOnly() != 0 {
+        SigningLevel::Store
+    } else {
+        SigningLevel::Unchecked
+    })
+}
+
+type GetApplicationUserModelIdFn = unsafe extern "system" fn(HANDLE, *mut UINT32, PWSTR) -> LONG;
+
+// `GetApplicationUserModelId` is only available since Windows 8 so it's loaded at runtime.
+static GET_APPLICATION_USER_MODEL_ID: Lazy<Option<GetApplicationUserModelIdFn>> =
+    Lazy::new(|| unsafe {
+        let name: Vec<u16> = "kernel32.dll\0".encode_utf16().collect();
+        let kernel32 = GetModuleHandleW(name.as_ptr());
+        if kernel32.is_null() {
+            return None;
+        }
+        let func = GetProcAddress(kernel32, b"GetApplicationUserModelId\0".as_ptr() as _);
+        if func.is_null() {
+            None
+        } else {
+            Some(std::mem::transmute::<FARPROC, GetApplicationUserModelIdFn>(
+                func,
             ))
         }
+    });
+
+type IsProcessInWDAGContainerFn = unsafe extern "system" fn(LPVOID, *mut BOOL) -> HRESULT;
+
+// `IsProcessInWDAGContainer` is only available since Windows 10 1803, in a DLL which isn't loaded
+// by default.
+static IS_PROCESS_IN_WDAG_CONTAINER: Lazy<Option<IsProcessInWDAGContainerFn>> =
+    Lazy::new(|| unsafe {
+        let name: Vec<u16> = "isolatedwindowsenvironmentutils.dll\0"
+            .encode_utf16()
+            .collect();
+        let module = LoadLibraryExW(name.as_ptr(), null_mut(), LOAD_LIBRARY_SEARCH_SYSTEM32);
+        if module.is_null() {
+            return None;
+        }
+        let func = GetProcAddress(module, b"IsProcessInWDAGContainer\0".as_ptr() as _);
+        if func.is_null() {
+            None
+        } else {
+            Some(std::mem::transmute::<FARPROC, IsProcessInWDAGContainerFn>(
+                func,
+            ))
+        }
+    });
+
+// Returns `true` if the current process runs inside a Windows Defender Application Guard
+// container.
+fn is_in_wdag_container() -> Option<bool> {
+    let func = (*IS_PROCESS_IN_WDAG_CONTAINER)?;
+    let mut in_container = FALSE;
+    if unsafe { func(null_mut(), &mut in_container) } < 0 {
+        sysinfo_debug!("IsProcessInWDAGContainer failed");
+        return None;
     }
+    Some(in_container != FALSE)
+}
 
-    pub(crate) fn new_full(
-        pid: Pid,
-        parent: Option<Pid>,
-        memory: u64,
-        virtual_memory: u64,
-        name: String,
-        now: u64,
-    ) -> Process {
-        if let Some(handle) = get_process_handler(pid) {
-            let mut h_mod = null_mut();
+// Maximum length of an AppUserModelID, including the null terminator.
+const APPLICATION_USER_MODEL_ID_MAX_LENGTH: usize = 130;
+
+unsafe fn get_app_user_model_id(process_handler: HANDLE) -> Option<String> {
+    let func = (*GET_APPLICATION_USER_MODEL_ID)?;
+    let mut buffer = [0u16; APPLICATION_USER_MODEL_ID_MAX_LENGTH];
+    let mut len = buffer.len() as UINT32;
+    let ret = func(process_handler, &mut len, buffer.as_mut_ptr());
+    // `APPMODEL_ERROR_NO_APPLICATION` is returned if the process doesn't have an AppUserModelID.
+    if ret != ERROR_SUCCESS as LONG {
+        return None;
+    }
+    Some(null_terminated_wchar_to_string(&buffer))
+}
+
+static PAGE_SIZE: Lazy<u64> = Lazy::new(|| unsafe {
+    let mut info: SYSTEM_INFO = zeroed();
+    GetSystemInfo(&mut info);
+    info.dwPageSize as u64
+});
+
+// The system times are only updated on each clock tick (every 15.6 ms by default), so the CPU
+// usage can't be computed over shorter intervals.
+const MIN_IDLE_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+// Returns the number of pages of the working set which aren't shared with other processes.
+unsafe fn get_private_working_set_pages(process_handler: HANDLE, memory: u64) -> Option<u64> {
+    // The buffer starts with the number of entries, followed by the entries themselves which are
+    // all `ULONG_PTR`s. We take some margin in case the working set grows in the meantime.
+    let mut nb_entries = (memory / *PAGE_SIZE) as usize + 1024;
+
+    loop {
+        let mut buffer: Vec<usize> = vec![0; nb_entries + 1];
+        if QueryWorkingSet(
+            process_handler,
+            buffer.as_mut_ptr() as _,
+            (buffer.len() * size_of::<usize>()) as DWORD,
+        ) != FALSE
+        {
+            let len = std::cmp::min(buffer[0], nb_entries);
+            return Some(
+                buffer[1..=len]
+                    .iter()
+                    .filter(|&&flags| PSAPI_WORKING_SET_BLOCK { Flags: flags }.Shared() == 0)
+                    .count() as u64,
+            );
+        }
+        // When the buffer is too small, the first entry is filled with the needed size.
+        if buffer[0] <= nb_entries {
+            sysinfo_debug!("QueryWorkingSet failed");
+            return None;
+        }
+        nb_entries = buffer[0] + 1024;
+    }
+}
+
+// Not defined in `winapi`. The information class is named `ProcessActivityThrottleState` in
+// `ntapi` and `ProcessPowerThrottlingState` in the Windows SDK.
+#[allow(non_snake_case)]
+#[repr(C)]
+struct PROCESS_POWER_THROTTLING_STATE {
+    Version: ULONG,
+    ControlMask: ULONG,
+    StateMask: ULONG,
+}
+
+const PROCESS_POWER_THROTTLING_CURRENT_VERSION: ULONG = 1;
+const PROCESS_POWER_THROTTLING_EXECUTION_SPEED: ULONG = 0x1;
+
+unsafe fn get_power_throttling_state(
+    process_handler: HANDLE,
+) -> Option<PROCESS_POWER_THROTTLING_STATE> {
+    let mut state = PROCESS_POWER_THROTTLING_STATE {
+        Version: PROCESS_POWER_THROTTLING_CURRENT_VERSION,
+        ControlMask: 0,
+        StateMask: 0,
+    };
+    let status = NtQueryInformationProcess(
+        process_handler,
+        ProcessActivityThrottleState,
+        &mut state as *mut _ as _,
+        size_of::<PROCESS_POWER_THROTTLING_STATE>() as _,
+        null_mut(),
+    );
+    if !NT_SUCCESS(status) {
+        sysinfo_debug!(
+            "NtQueryInformationProcess(ProcessPowerThrottlingState) failed: {}",
+            status
+        );
+        return None;
+    }
+    Some(state)
+}
+
+// Some system processes are always started by the same parent. Any other parent means that it
+// was spoofed.
+const KNOWN_PARENTS: &[(&str, &[&str])] = &[
+    ("smss.exe", &["smss.exe", "System"]),
+    ("csrss.exe", &["smss.exe"]),
+    ("wininit.exe", &["smss.exe"]),
+    ("winlogon.exe", &["smss.exe"]),
+    ("services.exe", &["wininit.exe"]),
+    ("lsass.exe", &["wininit.exe"]),
+    ("lsaiso.exe", &["wininit.exe"]),
+    ("svchost.exe", &["services.exe", "MsMpEng.exe"]),
+    ("taskhostw.exe", &["svchost.exe"]),
+    ("userinit.exe", &["winlogon.exe"]),
+];
+
+// Returns the creation time of the process, in 100-nanosecond units.
+unsafe fn get_creation_time(process_handler: HANDLE) -> Option<u64> {
+    let mut fstart: FILETIME = zeroed();
+    let mut x = zeroed();
+    if GetProcessTimes(process_handler, &mut fstart, &mut x, &mut x, &mut x) == FALSE {
+        return None;
+    }
+    Some(super::utils::filetime_to_u64(fstart))
+}
+
+fn machine_type_from_raw(machine: u16) -> MachineType {
+    match machine {
+        IMAGE_FILE_MACHINE_I386 => MachineType::X86,
+        IMAGE_FILE_MACHINE_AMD64 => MachineType::X64,
+        IMAGE_FILE_MACHINE_ARMNT => MachineType::Arm,
+        IMAGE_FILE_MACHINE_ARM64 => MachineType::Arm64,
+        x => MachineType::Unknown(x),
+    }
+}
+
+// `winapi` declares the mask as a `DWORD`, which would only cover the first 32 processors.
+#[link(name = "kernel32")]
+extern "system" {
+    fn SetProcessAffinityMask(hProcess: HANDLE, dwProcessAffinityMask: DWORD_PTR) -> BOOL;
+}
+
+fn get_session_id(pid: Pid) -> Option<u32> {
+    let mut session_id = 0;
+    if unsafe { ProcessIdToSessionId(pid.0 as DWORD, &mut session_id) } == FALSE {
+        sysinfo_debug!("ProcessIdToSessionId failed");
+        return None;
+    }
+    Some(session_id)
+}
+
+fn get_handle_count(process_handler: HANDLE) -> u32 {
+    if process_handler.is_null() {
+        return 0;
+    }
+    let mut count = 0;
+    if unsafe { GetProcessHandleCount(process_handler, &mut count) } == FALSE {
+        sysinfo_debug!("GetProcessHandleCount failed");
+        return 0;
+    }
+    count
+}
+
+fn priority_from_raw(class: DWORD) -> Option<Priority> {
+    match class {
+        IDLE_PRIORITY_CLASS => Some(Priority::Idle),
+        BELOW_NORMAL_PRIORITY_CLASS => Some(Priority::BelowNormal),
+        NORMAL_PRIORITY_CLASS => Some(Priority::Normal),
+        ABOVE_NORMAL_PRIORITY_CLASS => Some(Priority::AboveNormal),
+        HIGH_PRIORITY_CLASS => Some(Priority::High),
+        REALTIME_PRIORITY_CLASS => Some(Priority::Realtime),
+        _ => None,
+    }
+}
+
+impl Process {
+    pub(crate) fn new_from_pid(
+        pid: Pid,
+        now: u64,
+        refresh_kind: crate::ProcessRefreshKind,
+    ) -> Option<Process> {
+        unsafe {
+            let process_handler = get_process_handler(pid)?;
+            let mut info: MaybeUninit<PROCESS_BASIC_INFORMATION> = MaybeUninit::uninit();
+            if NtQueryInformationProcess(
+                process_handler,
+                ProcessBasicInformation,
+                info.as_mut_ptr() as *mut _,
+                size_of::<PROCESS_BASIC_INFORMATION>() as _,
+                null_mut(),
+            ) != 0
+            {
+                CloseHandle(process_handler);
+                return None;
+            }
+            let info = info.assume_init();
+            // `process_handler` is now owned by the returned `Process`.
+            Some(Process::new_with_handle(
+                pid,
+                if info.InheritedFromUniqueProcessId as usize != 0 {
+                    Some(Pid(info.InheritedFromUniqueProcessId as _))
+                } else {
+                    None
+                },
+                process_handler,
+                now,
+                refresh_kind,
+            ))
+        }
+    }
+
+    pub(crate) fn new_full(
+        pid: Pid,
+        parent: Option<Pid>,
+        memory: u64,
+        virtual_memory: u64,
+        name: String,
+        now: u64,
+        refresh_kind: crate::ProcessRefreshKind,
+    ) -> Process {
+        if let Some(handle) = get_process_handler(pid) {
+            unsafe {
+                let exe = get_exe(handle, refresh_kind.exe());
+                let mut root = exe.clone();
+                root.pop();
+                let (arch, params) = get_process_params(handle);
+                let mut params_error = None;
+                let (cmd, environ, cwd) = match params {
+                    Ok(args) => args,
+                    Err(e) => {
+                        sysinfo_debug!("Failed to get process parameters: {}", e);
+                        params_error = Some(e);
+                        (Vec::new(), Vec::new(), PathBuf::new())
+                    }
+                };
+                let (start_time, start_time_nanos, run_time) = get_start_and_run_time(handle, now);
+                Process {
+                    handle: PtrWrapper(handle),
+                    name,
+                    pid,
+                    parent,
+                    cmd,
+                    environ,
+                    exe,
+                    cwd,
+                    root,
+                    status: ProcessStatus::Run,
+                    memory,
+                    old_memory: memory,
+                    peak_memory: memory,
+                    pagefile_usage: 0,
+                    page_faults: 0,
+                    old_page_faults: 0,
+                    virtual_memory,
+                    cpu_usage: 0.,
+                    cpu_usage_machine_share: 0.,
+                    cpu_calc_values: CPUsageCalculationValues::new(),
+                    kernel_time: 0,
+                    user_time: 0,
+                    start_time,
+                    start_time_nanos,
+                    run_time,
+                    updated: true,
+                    old_read_bytes: 0,
+                    old_written_bytes: 0,
+                    read_bytes: 0,
+                    written_bytes: 0,
+                    old_read_operations: 0,
+                    old_written_operations: 0,
+                    read_operations: 0,
+                    written_operations: 0,
+                    thread_count: 0,
+                    user_id: None,
+                    user_name: None,
+                    elevated: None,
+                    integrity_level: None,
+                    handle_count: 0,
+                    session_id: None,
+                    arch,
+                    params_error,
+                    modules: Vec::new(),
+                    ready_time: None,
+                    user_data: None,
+                    cpu_history: Vec::new(),
+                    dirty: true,
+                    first_update_done: false,
+                    io_counters_read: false,
+                    last_io_time: None,
+                    network_read: false,
+                    old_network_rx_bytes: 0,
+                    old_network_tx_bytes: 0,
+                    network_rx_bytes: 0,
+                    network_tx_bytes: 0,
+                    tcp_connections: HashMap::new(),
+                }
+            }
+        } else {
+            Process {
+                handle: PtrWrapper(null_mut()),
+                name,
+                pid,
+                parent,
+                cmd: Vec::new(),
+                environ: Vec::new(),
+                exe: if refresh_kind.exe() {
+                    get_executable_path(pid)
+                } else {
+                    PathBuf::new()
+                },
+                cwd: PathBuf::new(),
+                root: PathBuf::new(),
+                status: ProcessStatus::Run,
+                memory,
+                old_memory: memory,
+                peak_memory: memory,
+                pagefile_usage: 0,
+                page_faults: 0,
+                old_page_faults: 0,
+                virtual_memory,
+                cpu_usage: 0.,
+                cpu_usage_machine_share: 0.,
+                cpu_calc_values: CPUsageCalculationValues::new(),
+                kernel_time: 0,
+                user_time: 0,
+                start_time: 0,
+                start_time_nanos: 0,
+                run_time: 0,
+                updated: true,
+                old_read_bytes: 0,
+                old_written_bytes: 0,
+                read_bytes: 0,
+                written_bytes: 0,
+                old_read_operations: 0,
+                old_written_operations: 0,
+                read_operations: 0,
+                written_operations: 0,
+                thread_count: 0,
+                user_id: None,
+                user_name: None,
+                elevated: None,
+                integrity_level: None,
+                handle_count: 0,
+                session_id: None,
+                arch: None,
+                params_error: None,
+                modules: Vec::new(),
+                ready_time: None,
+                user_data: None,
+                cpu_history: Vec::new(),
+                dirty: true,
+                first_update_done: false,
+                io_counters_read: false,
+                last_io_time: None,
+                network_read: false,
+                old_network_rx_bytes: 0,
+                old_network_tx_bytes: 0,
+                network_rx_bytes: 0,
+                network_tx_bytes: 0,
+                tcp_connections: HashMap::new(),
+            }
+        }
+    }
+
+    fn new_with_handle(
+        pid: Pid,
+        parent: Option<Pid>,
+        process_handler: HANDLE,
+        now: u64,
+        refresh_kind: crate::ProcessRefreshKind,
+    ) -> Process {
+        unsafe {
+            let exe = get_exe(process_handler, refresh_kind.exe());
+            let name = exe
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let mut root = exe.clone();
+            root.pop();
+            let (arch, params) = get_process_params(process_handler);
+            let mut params_error = None;
+            let (cmd, environ, cwd) = match params {
+                Ok(args) => args,
+                Err(e) => {
+                    sysinfo_debug!("Failed to get process parameters: {}", e);
+                    params_error = Some(e);
+                    (Vec::new(), Vec::new(), PathBuf::new())
+                }
+            };
+            let (start_time, start_time_nanos, run_time) =
+                get_start_and_run_time(process_handler, now);
+            let (sys, user) = get_process_cpu_times(process_handler);
+            Process {
+                handle: PtrWrapper(process_handler),
+                name,
+                pid,
+                parent,
+                cmd,
+                environ,
+                exe,
+                cwd,
+                root,
+                status: ProcessStatus::Run,
+                memory: 0,
+                old_memory: 0,
+                peak_memory: 0,
+                pagefile_usage: 0,
+                page_faults: 0,
+                old_page_faults: 0,
+                virtual_memory: 0,
+                cpu_usage: 0.,
+                cpu_usage_machine_share: 0.,
+                cpu_calc_values: CPUsageCalculationValues::with_times(
+                    sys,
+                    user,
+                    &get_system_times(),
+                ),
+                kernel_time: 0,
+                user_time: 0,
+                start_time,
+                start_time_nanos,
+                run_time,
+                updated: true,
+                old_read_bytes: 0,
+                old_written_bytes: 0,
+                read_bytes: 0,
+                written_bytes: 0,
+                old_read_operations: 0,
+                old_written_operations: 0,
+                read_operations: 0,
+                written_operations: 0,
+                thread_count: 0,
+                user_id: None,
+                user_name: None,
+                elevated: None,
+                integrity_level: None,
+                handle_count: 0,
+                session_id: None,
+                arch,
+                params_error,
+                modules: Vec::new(),
+                ready_time: None,
+                user_data: None,
+                cpu_history: Vec::new(),
+                dirty: true,
+                first_update_done: false,
+                io_counters_read: false,
+                last_io_time: None,
+                network_read: false,
+                old_network_rx_bytes: 0,
+                old_network_tx_bytes: 0,
+                network_rx_bytes: 0,
+                network_tx_bytes: 0,
+                tcp_connections: HashMap::new(),
+            }
+        }
+    }
+
+    pub(crate) fn update(
+        &mut self,
+        refresh_kind: crate::ProcessRefreshKind,
+        nb_processors: u64,
+        system_times: &SystemTimes,
+        cpu_history_len: usize,
+        now: u64,
+        snapshot: Option<&ProcessSnapshot>,
+    ) {
+        let old_cpu_usage = self.cpu_usage;
+        if let Some(snapshot) = snapshot {
+            // The start time couldn't be read if the process couldn't be opened.
+            if self.start_time == 0 {
+                self.start_time = filetime_to_unix_secs(snapshot.create_time);
+                self.start_time_nanos = filetime_to_unix_nanos(snapshot.create_time);
+            }
+        }
+        if refresh_kind.cpu() {
+            match snapshot {
+                Some(snapshot) => compute_cpu_usage_from_times(
+                    self,
+                    nb_processors,
+                    system_times,
+                    snapshot.kernel_time,
+                    snapshot.user_time,
+                ),
+                None => compute_cpu_usage(self, nb_processors, system_times),
+            }
+            if cpu_history_len != 0 {
+                self.cpu_history.push(self.cpu_usage);
+            }
+            self.truncate_cpu_history(cpu_history_len);
+        }
+        if refresh_kind.disk_usage() {
+            match snapshot {
+                Some(snapshot) => set_io_counters(self, &snapshot.io_counters, now),
+                None => update_disk_usage(self, now),
+            }
+        }
+        if refresh_kind.user() && self.user_id.is_none() && !self.handle.is_null() {
+            if let Some((uid, name)) = unsafe { get_process_user(*self.handle) } {
+                self.user_id = Some(uid);
+                self.user_name = Some(name);
+            }
+        }
+        if refresh_kind.elevation() && self.elevated.is_none() && !self.handle.is_null() {
+            self.elevated = unsafe { is_process_elevated(*self.handle) };
+        }
+        // The integrity level of a token can be lowered while the process is running.
+        if refresh_kind.integrity_level() && !self.handle.is_null() {
+            self.integrity_level = unsafe { get_process_integrity_level(*self.handle) };
+        }
+        if !self.handle.is_null()
+            && (refresh_kind.cmd()
+                || (self.cmd.is_empty()
+                    && !matches!(self.params_error, Some(e) if e.is_permanent())))
+        {
+            self.refresh_params();
+        } else if refresh_kind.cwd() && !self.handle.is_null() {
+            self.refresh_cwd();
+        }
+        if refresh_kind.modules() && !self.handle.is_null() {
+            self.modules = unsafe { get_module_infos(*self.handle) };
+        }
+        if refresh_kind.network() {
+            let connections = match snapshot {
+                Some(snapshot) => snapshot.tcp_connections.clone(),
+                None => get_tcp_connections_usage(self.pid),
+            };
+            self.refresh_network_usage(connections);
+        }
+        if refresh_kind.session() && self.session_id.is_none() {
+            self.session_id = match snapshot {
+                Some(snapshot) => Some(snapshot.session_id),
+                None => get_session_id(self.pid),
+            };
+        }
+        if refresh_kind.handles() {
+            self.handle_count = match snapshot {
+                Some(snapshot) => snapshot.handle_count,
+                None => get_handle_count(*self.handle),
+            };
+        }
+        self.dirty = !self.first_update_done
+            || (self.cpu_usage - old_cpu_usage).abs() >= CPU_USAGE_DIRTY_THRESHOLD
+            || self.memory != self.old_memory
+            || (refresh_kind.disk_usage()
+                && (self.read_bytes != self.old_read_bytes
+                    || self.written_bytes != self.old_written_bytes));
+        self.first_update_done = true;
+        self.run_time = now - self.start_time();
+        self.updated = true;
+    }
+
+    /// Returns `true` if the CPU usage, the memory usage or the disk usage of this process
+    /// changed during the last refresh. A new process is always considered as changed.
+    ///
+    /// CPU usage variations smaller than 0.1% are ignored.
+    ///
+    /// ```no_run
+    /// use sysinfo::{ProcessExt, System, SystemExt};
+    ///
+    /// let mut s = System::new_all();
+    /// s.refresh_processes();
+    /// for process in s.processes().values().filter(|p| p.is_dirty()) {
+    ///     println!("{} changed", process.name());
+    /// }
+    /// ```
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    // Only keeps the `max_len` latest CPU usage values.
+    pub(crate) fn truncate_cpu_history(&mut self, max_len: usize) {
+        if self.cpu_history.len() > max_len {
+            let extra = self.cpu_history.len() - max_len;
+            self.cpu_history.drain(..extra);
+        }
+    }
+
+    /// Returns the CPU usage values (in %) computed by the latest refreshes of this process, from
+    /// the oldest to the newest.
+    ///
+    /// The number of kept values is set with [`System::set_cpu_history_len`], which is `0` by
+    /// default. The history is kept as long as the process is alive.
+    ///
+    /// [`System::set_cpu_history_len`]: crate::System::set_cpu_history_len
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let mut s = System::new();
+    /// s.set_cpu_history_len(10);
+    /// s.refresh_processes();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{:?}", process.cpu_history());
+    /// }
+    /// ```
+    pub fn cpu_history(&self) -> &[f32] {
+        &self.cpu_history
+    }
+
+    /// Returns the time (in seconds since UNIX epoch) of the last refresh during which the
+    /// number of bytes read or written by this process changed.
+    ///
+    /// Only the refreshes with the disk usage enabled are taken into account. Returns `None` if
+    /// no change was seen since this process was added to the list.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{:?}", process.last_io_time());
+    /// }
+    /// ```
+    pub fn last_io_time(&self) -> Option<u64> {
+        self.last_io_time
+    }
+
+    /// Returns the time this process spent running in kernel mode since it started.
+    ///
+    /// It's only updated when the process is refreshed with
+    /// [`ProcessRefreshKind::cpu`][crate::ProcessRefreshKind::cpu] set, like
+    /// [`ProcessExt::cpu_usage`].
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{:?}", process.kernel_time());
+    /// }
+    /// ```
+    pub fn kernel_time(&self) -> Duration {
+        Duration::from_nanos(self.kernel_time * 100)
+    }
+
+    /// Returns the time this process spent running in user mode since it started.
+    ///
+    /// It's only updated when the process is refreshed with
+    /// [`ProcessRefreshKind::cpu`][crate::ProcessRefreshKind::cpu] set, like
+    /// [`ProcessExt::cpu_usage`].
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{:?}", process.user_time());
+    /// }
+    /// ```
+    pub fn user_time(&self) -> Duration {
+        Duration::from_nanos(self.user_time * 100)
+    }
+
+    /// Returns the CPU time consumed by this process since it started, which is the sum of
+    /// [`Process::kernel_time`] and [`Process::user_time`].
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{} ms", process.accumulated_cpu_time().as_millis());
+    /// }
+    /// ```
+    pub fn accumulated_cpu_time(&self) -> Duration {
+        self.kernel_time() + self.user_time()
+    }
+
+    /// Returns the number of handles opened by this process, like the "Handles" column of the
+    /// Task Manager.
+    ///
+    /// It's only updated when the process is refreshed with
+    /// [`ProcessRefreshKind::handles`][crate::ProcessRefreshKind::handles] set. Returns `0` if
+    /// it couldn't be retrieved, which is the case for protected processes.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, ProcessRefreshKind, System, SystemExt};
+    ///
+    /// let mut s = System::new();
+    /// s.refresh_process_specifics(Pid::from(1337), ProcessRefreshKind::new().with_handles());
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{} handles", process.handle_count());
+    /// }
+    /// ```
+    pub fn handle_count(&self) -> u32 {
+        self.handle_count
+    }
+
+    /// Returns the Terminal Services session this process runs in. Services run in the session
+    /// `0` while interactive users (local or connected through RDP) get their own sessions.
+    ///
+    /// It's only retrieved when the process is refreshed with
+    /// [`ProcessRefreshKind::session`][crate::ProcessRefreshKind::session] set. Returns `None`
+    /// if it couldn't be retrieved.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, ProcessRefreshKind, System, SystemExt};
+    ///
+    /// let mut s = System::new();
+    /// s.refresh_process_specifics(Pid::from(1337), ProcessRefreshKind::new().with_session());
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{:?}", process.session_id());
+    /// }
+    /// ```
+    pub fn session_id(&self) -> Option<u32> {
+        self.session_id
+    }
+
+    /// Returns the number of handles this process has opened on objects of the given type
+    /// (`"File"`, `"Key"`, `"Event"`...). The type name comparison is case-insensitive.
+    ///
+    /// The system handle table is queried every time this method is called, so it shouldn't be
+    /// called in a hot loop. Returns `None` if the handle table couldn't be retrieved or if the
+    /// object type doesn't exist.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{:?} file handles", process.handle_count_of_type("File"));
+    /// }
+    /// ```
+    pub fn handle_count_of_type(&self, type_name: &str) -> Option<u32> {
+        let type_index = get_object_type_index(type_name)?;
+        let handles = get_process_handles(self.pid)?;
+        Some(
+            handles
+                .iter()
+                .filter(|h| h.ObjectTypeIndex == type_index)
+                .count() as u32,
+        )
+    }
+
+    /// Returns the handles opened by this process, with the name of the object they target when
+    /// it has one (files, registry keys, named events...).
+    ///
+    /// The system handle table is queried every time this method is called and retrieving the
+    /// names can be slow, so it shouldn't be called in a hot loop. Retrieving the name of some
+    /// file handles can block forever: such handles are given up on after a short delay.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     for handle in process.open_handles() {
+    ///         println!("{}: {:?}", handle.type_name, handle.name);
+    ///     }
+    /// }
+    /// ```
+    pub fn open_handles(&self) -> Vec<HandleInfo> {
+        let handles = match get_process_handles(self.pid) {
+            Some(handles) => handles,
+            None => return Vec::new(),
+        };
+        let file_type = get_object_type_index("File");
+
+        unsafe {
+            let process = OpenProcess(PROCESS_DUP_HANDLE, FALSE, self.pid.0 as DWORD);
+            let handles = handles
+                .iter()
+                .map(|h| HandleInfo {
+                    type_name: get_object_type_name(h.ObjectTypeIndex)
+                        .unwrap_or_default()
+                        .to_owned(),
+                    name: if process.is_null() {
+                        None
+                    } else {
+                        get_handle_name(
+                            process,
+                            h.HandleValue,
+                            Some(h.ObjectTypeIndex) == file_type,
+                        )
+                    },
+                })
+                .collect();
+            if !process.is_null() {
+                CloseHandle(process);
+            }
+            handles
+        }
+    }
+
+    /// Returns the environment variables of this process whose name starts with `prefix`, as
+    /// `(name, value)` pairs. As on Windows environment variable names are case-insensitive, the
+    /// prefix comparison is case-insensitive too.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     for (name, value) in process.environ_with_prefix("DOTNET_") {
+    ///         println!("{}={}", name, value);
+    ///     }
+    /// }
+    /// ```
+    pub fn environ_with_prefix(&self, prefix: &str) -> Vec<(String, String)> {
+        self.environ_vars()
+            .into_iter()
+            .filter_map(|(name, value)| match name.get(..prefix.len()) {
+                Some(start) if start.eq_ignore_ascii_case(prefix) => {
+                    Some((name.to_owned(), value.to_owned()))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns how DLLs are looked up when loaded by this process.
+    ///
+    /// `SafeDllSearchMode` is read from the registry (it is enabled when the value is missing)
+    /// whereas the other fields come from the image load mitigation policy of the process.
+    /// Returns `None` if the policy couldn't be retrieved.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{:?}", process.dll_search_mode());
+    /// }
+    /// ```
+    pub fn dll_search_mode(&self) -> Option<DllSearchMode> {
+        if self.handle.is_null() {
+            return None;
+        }
+        let policy = unsafe { get_image_load_policy(*self.handle)? };
+        let safe_dll_search_mode = get_reg_value_u32(
+            HKEY_LOCAL_MACHINE,
+            "SYSTEM\\CurrentControlSet\\Control\\Session Manager",
+            "SafeDllSearchMode",
+        )
+        .map(|v| u32::from_ne_bytes(v) != 0)
+        .unwrap_or(true);
+        Some(DllSearchMode {
+            safe_dll_search_mode,
+            prefer_system32_images: policy.PreferSystem32Images() != 0,
+            no_remote_images: policy.NoRemoteImages() != 0,
+            no_low_mandatory_label_images: policy.NoLowMandatoryLabelImages() != 0,
+        })
+    }
+
+    /// Returns for how long the threads of this process which are ready to run have been waiting
+    /// to be scheduled, summed over all of them.
+    ///
+    /// A high value means the process is starved of CPU time rather than busy. It is updated
+    /// when the processes list is refreshed (with [`SystemExt::refresh_processes`] for example)
+    /// and the value has a precision of one clock tick (around 15ms by default). Returns `None`
+    /// if it hasn't been computed yet.
+    ///
+    /// [`SystemExt::refresh_processes`]: crate::SystemExt::refresh_processes
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{:?}", process.ready_time());
+    /// }
+    /// ```
+    pub fn ready_time(&self) -> Option<Duration> {
+        self.ready_time
+    }
+
+    /// Returns the private working set of this process in bytes, i.e. the part of its working
+    /// set which isn't shared with other processes. It is what Task Manager shows as the memory
+    /// of a process.
+    ///
+    /// Unlike [`ProcessExt::memory`], summing it over all processes doesn't count shared pages
+    /// more than once. The working set is walked every time this method is called. Returns
+    /// `None` if it couldn't be retrieved.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{:?} bytes", process.private_working_set());
+    /// }
+    /// ```
+    pub fn private_working_set(&self) -> Option<u64> {
+        if self.handle.is_null() {
+            return None;
+        }
+        unsafe { get_private_working_set_pages(*self.handle, self.memory) }
+            .map(|nb_pages| nb_pages * *PAGE_SIZE)
+    }
+
+    /// Returns the data attached to this process with [`Process::set_user_data`].
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let mut s = System::new_all();
+    /// if let Some(process) = s.process_mut(Pid::from(1337)) {
+    ///     process.set_user_data(Box::new("my tag"));
+    /// }
+    /// s.refresh_processes();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     let tag = process.user_data().and_then(|data| data.downcast_ref::<&str>());
+    ///     println!("{:?}", tag);
+    /// }
+    /// ```
+    pub fn user_data(&self) -> Option<&(dyn Any + Send + Sync)> {
+        self.user_data.as_deref()
+    }
+
+    /// Attaches `data` to this process, replacing the previous one if any.
+    ///
+    /// The data is kept when the processes are refreshed, for as long as the process is alive.
+    /// If its PID is reused by another process, the new process won't have any data attached.
+    pub fn set_user_data(&mut self, data: Box<dyn Any + Send + Sync>) {
+        self.user_data = Some(data);
+    }
+
+    /// Returns `true` if this process is running inside a server silo, i.e. in a process-isolated
+    /// Windows container.
+    ///
+    /// Processes running in a nested job inside of a silo aren't detected. Returns `None` if the
+    /// information couldn't be retrieved (it requires administrator privileges).
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{:?}", process.is_in_silo());
+    /// }
+    /// ```
+    pub fn is_in_silo(&self) -> Option<bool> {
+        let job_id = get_process_extension(self.pid)?.JobObjectId;
+        Some(job_id != 0 && get_server_silo_ids()?.contains(&job_id))
+    }
+
+    /// Returns `true` if this process is running inside a Windows Defender Application Guard
+    /// container.
+    ///
+    /// Unlike the ones of a server silo (see [`Process::is_in_silo`]), the processes of an
+    /// Application Guard container run in their own lightweight virtual machine: they can't be
+    /// seen from the host, and only the processes of the container can be seen from inside of it.
+    /// So this is the same for all the processes, and it's answered by `IsProcessInWDAGContainer`
+    /// for the current one. Returns `None` if it isn't available (before Windows 10 1803 or
+    /// without the Application Guard component) or if it failed.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{:?}", process.is_in_application_guard());
+    /// }
+    /// ```
+    pub fn is_in_application_guard(&self) -> Option<bool> {
+        is_in_wdag_container()
+    }
+
+    /// Returns the change of the memory usage (in KB) of this process since the previous
+    /// refresh, like [`ProcessExt::disk_usage`] does for the disk usage.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let mut s = System::new_all();
+    /// s.refresh_processes();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{} KB", process.memory_delta());
+    /// }
+    /// ```
+    pub fn memory_delta(&self) -> i64 {
+        (self.memory as i64 - self.old_memory as i64) / 1_000
+    }
+
+    /// Returns the signals which can actually be sent to this process.
+    ///
+    /// Unlike [`SystemExt::SUPPORTED_SIGNALS`], it checks that the access rights needed to send
+    /// each signal can be obtained on this process, which isn't the case for protected processes
+    /// or for processes owned by other users for example.
+    ///
+    /// [`SystemExt::SUPPORTED_SIGNALS`]: crate::SystemExt::SUPPORTED_SIGNALS
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{:?}", process.supported_signals());
+    /// }
+    /// ```
+    pub fn supported_signals(&self) -> Vec<Signal> {
+        if self.pid.0 == 0 {
+            return Vec::new();
+        }
+        super::system::supported_signals()
+            .iter()
+            .copied()
+            .filter(|signal| match super::system::convert_signal(*signal) {
+                Some(SignalKind::Terminate) => unsafe {
+                    let handle = OpenProcess(PROCESS_TERMINATE, FALSE, self.pid.0 as DWORD);
+                    if handle.is_null() {
+                        false
+                    } else {
+                        CloseHandle(handle);
+                        true
+                    }
+                },
+                // It doesn't need any access rights: whether it reaches the process is only
+                // known when it's sent.
+                Some(SignalKind::Graceful) => true,
+                None => false,
+            })
+            .collect()
+    }
+
+    /// Returns the full path of the main image of this process.
+    ///
+    /// Unlike [`ProcessExt::exe`], which is only retrieved when the process is added (and without
+    /// enumerating its modules unless [`ProcessRefreshKind::exe`][crate::ProcessRefreshKind::exe]
+    /// is set), it's retrieved on every call. `QueryFullProcessImageNameW` is tried first, which only needs limited access rights, then
+    /// the modules of the process and then the `ImagePathName` field of the process parameters.
+    /// An empty path is returned if all of them failed.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{}", process.image_path().display());
+    /// }
+    /// ```
+    pub fn image_path(&self) -> PathBuf {
+        if !self.exe.as_os_str().is_empty() {
+            return self.exe.clone();
+        }
+        unsafe {
+            if !self.handle.is_null() {
+                let exe = get_exe(*self.handle, true);
+                if !exe.as_os_str().is_empty() {
+                    return exe;
+                }
+                if let Some(path) = get_image_path_from_params(*self.handle) {
+                    return path;
+                }
+            } else if self.pid.0 != 0 {
+                // We couldn't get a handle with enough rights to read the process memory, but a
+                // limited one is enough for `QueryFullProcessImageNameW`.
+                let handle = OpenProcess(
+                    PROCESS_QUERY_LIMITED_INFORMATION,
+                    FALSE,
+                    self.pid.0 as DWORD,
+                );
+                if !handle.is_null() {
+                    let path = get_full_image_name(handle);
+                    CloseHandle(handle);
+                    if let Some(path) = path {
+                        return path;
+                    }
+                }
+            }
+        }
+        PathBuf::new()
+    }
+
+    /// Returns `true` if the execution speed of this process is throttled (also known as
+    /// "EcoQoS" or "efficiency mode").
+    ///
+    /// It only reports the throttling explicitly requested for this process, not the one the
+    /// system could decide to apply on its own. Returns `None` if the information couldn't be
+    /// retrieved (it's only available since Windows 10 1709).
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{:?}", process.is_throttled());
+    /// }
+    /// ```
+    pub fn is_throttled(&self) -> Option<bool> {
+        if self.handle.is_null() {
+            return None;
+        }
+        let state = unsafe { get_power_throttling_state(*self.handle)? };
+        Some(
+            state.ControlMask & PROCESS_POWER_THROTTLING_EXECUTION_SPEED != 0
+                && state.StateMask & PROCESS_POWER_THROTTLING_EXECUTION_SPEED != 0,
+        )
+    }
+
+    /// Enables or disables the throttling of the execution speed of this process (also known as
+    /// "EcoQoS" or "efficiency mode").
+    ///
+    /// Returns `true` if the throttling state was successfully updated.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     if !process.set_throttled(true) {
+    ///         eprintln!("failed to throttle the process");
+    ///     }
+    /// }
+    /// ```
+    pub fn set_throttled(&self, throttled: bool) -> bool {
+        if self.pid.0 == 0 {
+            return false;
+        }
+        let mut state = PROCESS_POWER_THROTTLING_STATE {
+            Version: PROCESS_POWER_THROTTLING_CURRENT_VERSION,
+            ControlMask: PROCESS_POWER_THROTTLING_EXECUTION_SPEED,
+            StateMask: if throttled {
+                PROCESS_POWER_THROTTLING_EXECUTION_SPEED
+            } else {
+                0
+            },
+        };
+        unsafe {
+            let handle = OpenProcess(PROCESS_SET_INFORMATION, FALSE, self.pid.0 as DWORD);
+            if handle.is_null() {
+                return false;
+            }
+            let status = NtSetInformationProcess(
+                handle,
+                ProcessActivityThrottleState,
+                &mut state as *mut _ as _,
+                size_of::<PROCESS_POWER_THROTTLING_STATE>() as _,
+            );
+            CloseHandle(handle);
+            if !NT_SUCCESS(status) {
+                sysinfo_debug!(
+                    "NtSetInformationProcess(ProcessPowerThrottlingState) failed: {}",
+                    status
+                );
+                return false;
+            }
+            true
+        }
+    }
+
+    /// Returns the priority class of this process. Returns `None` if it couldn't be retrieved.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{:?}", process.priority());
+    /// }
+    /// ```
+    pub fn priority(&self) -> Option<Priority> {
+        if self.handle.is_null() {
+            return None;
+        }
+        let class = unsafe { GetPriorityClass(*self.handle) };
+        if class == 0 {
+            sysinfo_debug!("GetPriorityClass failed");
+            return None;
+        }
+        priority_from_raw(class)
+    }
+
+    /// Sets the priority class of this process.
+    ///
+    /// Returns `true` if the priority class was successfully updated. Setting
+    /// [`Priority::Realtime`] requires the `SeIncreaseBasePriorityPrivilege` privilege: without
+    /// it, Windows uses [`Priority::High`] instead and `false` is returned.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, Priority, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     if !process.set_priority(Priority::BelowNormal) {
+    ///         eprintln!("failed to update the priority of the process");
+    ///     }
+    /// }
+    /// ```
+    pub fn set_priority(&self, priority: Priority) -> bool {
+        if self.pid.0 == 0 {
+            return false;
+        }
+        let class = match priority {
+            Priority::Idle => IDLE_PRIORITY_CLASS,
+            Priority::BelowNormal => BELOW_NORMAL_PRIORITY_CLASS,
+            Priority::Normal => NORMAL_PRIORITY_CLASS,
+            Priority::AboveNormal => ABOVE_NORMAL_PRIORITY_CLASS,
+            Priority::High => HIGH_PRIORITY_CLASS,
+            Priority::Realtime => REALTIME_PRIORITY_CLASS,
+        };
+        unsafe {
+            let handle = OpenProcess(
+                PROCESS_SET_INFORMATION | PROCESS_QUERY_LIMITED_INFORMATION,
+                FALSE,
+                self.pid.0 as DWORD,
+            );
+            if handle.is_null() {
+                return false;
+            }
+            let mut ret = SetPriorityClass(handle, class) != FALSE;
+            if !ret {
+                sysinfo_debug!("SetPriorityClass failed");
+            } else if priority == Priority::Realtime {
+                // The call doesn't fail without the required privilege, so the resulting priority
+                // class has to be checked.
+                ret = GetPriorityClass(handle) == REALTIME_PRIORITY_CLASS;
+            }
+            CloseHandle(handle);
+            ret
+        }
+    }
+
+    /// Returns the CPU affinity mask of this process: bit `N` is set if the process can run on
+    /// the logical processor `N`. Returns `None` if it couldn't be retrieved.
+    ///
+    /// On systems with more than 64 logical processors, the processors are split in processor
+    /// groups and the mask only covers the processors of the group the process is running in.
+    /// If the process has threads running in multiple groups, `0` is returned.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     if let Some(mask) = process.cpu_affinity() {
+    ///         println!("{:#b}", mask);
+    ///     }
+    /// }
+    /// ```
+    pub fn cpu_affinity(&self) -> Option<u64> {
+        if self.handle.is_null() {
+            return None;
+        }
+        let mut process_mask = 0;
+        let mut system_mask = 0;
+        unsafe {
+            if GetProcessAffinityMask(*self.handle, &mut process_mask, &mut system_mask) == FALSE {
+                sysinfo_debug!("GetProcessAffinityMask failed");
+                return None;
+            }
+        }
+        Some(process_mask as u64)
+    }
+
+    /// Sets the CPU affinity mask of this process: bit `N` allows the process to run on the
+    /// logical processor `N`.
+    ///
+    /// Returns `true` if the affinity was successfully updated. `false` is returned if `mask` is
+    /// `0` or if it contains processors which don't exist. On systems with more than 64 logical
+    /// processors, the mask applies to the processor group the process is running in and setting
+    /// it fails if the process has threads running in multiple groups.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     // Only allow the process to run on the first two processors.
+    ///     if !process.set_cpu_affinity(0b11) {
+    ///         eprintln!("failed to update the affinity of the process");
+    ///     }
+    /// }
+    /// ```
+    pub fn set_cpu_affinity(&self, mask: u64) -> bool {
+        if self.pid.0 == 0 || mask == 0 || mask > DWORD_PTR::MAX as u64 {
+            return false;
+        }
+        unsafe {
+            let handle = OpenProcess(
+                PROCESS_SET_INFORMATION | PROCESS_QUERY_INFORMATION,
+                FALSE,
+                self.pid.0 as DWORD,
+            );
+            if handle.is_null() {
+                return false;
+            }
+            let mut process_mask = 0;
+            let mut system_mask = 0;
+            let ret =
+                if GetProcessAffinityMask(handle, &mut process_mask, &mut system_mask) == FALSE {
+                    sysinfo_debug!("GetProcessAffinityMask failed");
+                    false
+                } else if mask & !(system_mask as u64) != 0 {
+                    false
+                } else if SetProcessAffinityMask(handle, mask as DWORD_PTR) == FALSE {
+                    sysinfo_debug!("SetProcessAffinityMask failed");
+                    false
+                } else {
+                    true
+                };
+            CloseHandle(handle);
+            ret
+        }
+    }
+
+    /// Returns the maximum amount of memory (in KB) this process can commit, if it's part of a
+    /// job object which limits it. If it's part of multiple jobs, the smallest limit is returned.
+    ///
+    /// It can be compared to [`ProcessExt::virtual_memory`]. There is no API to get the jobs of a
+    /// process so the job objects opened by all the processes are checked, which is slow.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, ProcessExt, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     if let Some(limit) = process.memory_limit() {
+    ///         println!("{} KB left", limit.saturating_sub(process.virtual_memory()));
+    ///     }
+    /// }
+    /// ```
+    pub fn memory_limit(&self) -> Option<u64> {
+        if self.handle.is_null() {
+            return None;
+        }
+        let mut limit = None;
+        for job in get_process_jobs(*self.handle) {
+            unsafe {
+                let mut info: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = zeroed();
+                if QueryInformationJobObject(
+                    job,
+                    JobObjectExtendedLimitInformation,
+                    &mut info as *mut _ as _,
+                    size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as DWORD,
+                    null_mut(),
+                ) != FALSE
+                    && info.BasicLimitInformation.LimitFlags & JOB_OBJECT_LIMIT_PROCESS_MEMORY != 0
+                {
+                    let job_limit = info.ProcessMemoryLimit as u64 / 1_000;
+                    limit = Some(limit.map_or(job_limit, |l: u64| l.min(job_limit)));
+                }
+                CloseHandle(job);
+            }
+        }
+        limit
+    }
+
+    /// Returns how much more memory (in KB) this process can commit before reaching either the
+    /// commit limit of the system or the memory limit of its job objects (see
+    /// [`Process::memory_limit`]).
+    ///
+    /// The current commit of this process is read when this method is called and, like with
+    /// `memory_limit`, looking for its job objects is slow. Returns `None` if the information
+    /// couldn't be retrieved.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     if let Some(headroom) = process.commit_headroom() {
+    ///         println!("{} KB can still be committed", headroom);
+    ///     }
+    /// }
+    /// ```
+    pub fn commit_headroom(&self) -> Option<u64> {
+        if self.handle.is_null() {
+            return None;
+        }
+        let (commit, system_headroom) = unsafe {
+            let mut pmc: PROCESS_MEMORY_COUNTERS_EX = zeroed();
+            if GetProcessMemoryInfo(
+                *self.handle,
+                &mut pmc as *mut PROCESS_MEMORY_COUNTERS_EX as *mut c_void
+                    as *mut PROCESS_MEMORY_COUNTERS,
+                size_of::<PROCESS_MEMORY_COUNTERS_EX>() as DWORD,
+            ) == FALSE
+            {
+                sysinfo_debug!("GetProcessMemoryInfo failed on process {}", self.pid);
+                return None;
+            }
+            let mut mem_info: MEMORYSTATUSEX = zeroed();
+            mem_info.dwLength = size_of::<MEMORYSTATUSEX>() as DWORD;
+            if GlobalMemoryStatusEx(&mut mem_info) == FALSE {
+                sysinfo_debug!("GlobalMemoryStatusEx failed");
+                return None;
+            }
+            // `ullAvailPageFile` is what remains before reaching the system commit limit.
+            (
+                pmc.PrivateUsage as u64 / 1_000,
+                mem_info.ullAvailPageFile as u64 / 1_000,
+            )
+        };
+        match self.memory_limit() {
+            Some(limit) => Some(system_headroom.min(limit.saturating_sub(commit))),
+            None => Some(system_headroom),
+        }
+    }
+
+    /// Returns the resources used by all the processes of the job object this process is part
+    /// of. If it's part of nested jobs, the outermost one (the one with the most processes) is
+    /// used.
+    ///
+    /// There is no API to get the jobs of a process so the job objects opened by all the
+    /// processes are checked, which is slow. Returns `None` if this process isn't part of a job
+    /// or if the information couldn't be retrieved.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     if let Some(accounting) = process.job_accounting() {
+    ///         println!("{} processes", accounting.active_processes);
+    ///     }
+    /// }
+    /// ```
+    pub fn job_accounting(&self) -> Option<JobAccounting> {
+        if self.handle.is_null() {
+            return None;
+        }
+        let mut accounting: Option<JobAccounting> = None;
+        for job in get_process_jobs(*self.handle) {
+            unsafe {
+                let mut info: JOBOBJECT_BASIC_ACCOUNTING_INFORMATION = zeroed();
+                if QueryInformationJobObject(
+                    job,
+                    JobObjectBasicAccountingInformation,
+                    &mut info as *mut _ as _,
+                    size_of::<JOBOBJECT_BASIC_ACCOUNTING_INFORMATION>() as DWORD,
+                    null_mut(),
+                ) != FALSE
+                    && match accounting {
+                        Some(a) => info.TotalProcesses > a.total_processes,
+                        None => true,
+                    }
+                {
+                    // The times are in 100-nanosecond intervals.
+                    accounting = Some(JobAccounting {
+                        total_user_time: Duration::from_nanos(
+                            *info.TotalUserTime.QuadPart() as u64 * 100,
+                        ),
+                        total_kernel_time: Duration::from_nanos(
+                            *info.TotalKernelTime.QuadPart() as u64 * 100,
+                        ),
+                        total_page_faults: info.TotalPageFaultCount,
+                        total_processes: info.TotalProcesses,
+                        active_processes: info.ActiveProcesses,
+                        total_terminated_processes: info.TotalTerminatedProcesses,
+                    });
+                }
+                CloseHandle(job);
+            }
+        }
+        accounting
+    }
+
+    /// Returns `false` if the parent of this process looks spoofed (with
+    /// `PROC_THREAD_ATTRIBUTE_PARENT_PROCESS` for example).
+    ///
+    /// It is the case if the parent isn't running anymore, if it was started after this process
+    /// (meaning its PID was reused), or if this process is a well-known system process (like
+    /// `lsass.exe` or `svchost.exe`) which isn't started by its usual parent. This is only a
+    /// heuristic: a parent can legitimately exit before its children.
+    ///
+    /// Returns `None` if this process has no parent or if the information couldn't be retrieved.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     if process.parent_is_plausible() == Some(false) {
+    ///         println!("the parent of this process looks spoofed");
+    ///     }
+    /// }
+    /// ```
+    pub fn parent_is_plausible(&self) -> Option<bool> {
+        let parent = self.parent?;
+        unsafe {
+            let handle = OpenProcess(
+                PROCESS_QUERY_LIMITED_INFORMATION,
+                FALSE,
+                self.pid.0 as DWORD,
+            );
+            if handle.is_null() {
+                return None;
+            }
+            let start = get_creation_time(handle);
+            CloseHandle(handle);
+            let start = start?;
+
+            let parent_handle =
+                OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, FALSE, parent.0 as DWORD);
+            if parent_handle.is_null() {
+                return if std::io::Error::last_os_error().raw_os_error()
+                    == Some(ERROR_INVALID_PARAMETER as i32)
+                {
+                    // The parent doesn't exist anymore.
+                    Some(false)
+                } else {
+                    None
+                };
+            }
+            let parent_start = get_creation_time(parent_handle);
+            let parent_exe = get_full_image_name(parent_handle);
+            CloseHandle(parent_handle);
+
+            if parent_start? > start {
+                return Some(false);
+            }
+            if let Some((_, parents)) = KNOWN_PARENTS
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(&self.name))
+            {
+                let parent_name = match parent_exe {
+                    Some(ref exe) => exe.file_name()?.to_string_lossy(),
+                    // The "System" process has no image.
+                    None if parent.0 == 4 => "System".into(),
+                    None => return None,
+                };
+                return Some(parents.iter().any(|p| p.eq_ignore_ascii_case(&parent_name)));
+            }
+            Some(true)
+        }
+    }
+
+    /// Returns the checksum written in the PE header of the main image of this process, as read
+    /// from its memory.
+    ///
+    /// Comparing it with the one of the executable file on disk allows to detect some in-memory
+    /// patching. Note that most executables which aren't drivers or system DLLs have a checksum
+    /// of `0`. Returns `None` if the image header couldn't be read.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{:?}", process.image_checksum());
+    /// }
+    /// ```
+    pub fn image_checksum(&self) -> Option<u32> {
+        if self.handle.is_null() {
+            return None;
+        }
+        unsafe { get_image_checksum(*self.handle) }
+    }
+
+    /// Returns the names of the running services hosted by this process (like `svchost.exe`
+    /// processes), as known by the Service Control Manager.
+    ///
+    /// An empty list is returned if this process doesn't host any service.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, ProcessExt, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// for process in s.processes_by_exact_name("svchost.exe") {
+    ///     println!("{} {:?}", process.pid(), process.hosted_services());
+    /// }
+    /// ```
+    pub fn hosted_services(&self) -> Vec<String> {
+        if self.pid.0 == 0 {
+            return Vec::new();
+        }
+        get_services(SERVICE_ACTIVE)
+            .into_iter()
+            .filter(|service| service.pid as usize == self.pid.0)
+            .map(|service| service.name)
+            .collect()
+    }
+
+    /// Returns the names of the services hosted by this process (like
+    /// [`Process::hosted_services`]) along with their current state, as known by the Service
+    /// Control Manager.
+    ///
+    /// An empty list is returned if this process doesn't host any service.
+    ///
+    /// ```no_run
+    /// use sysinfo::{ProcessExt, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// for process in s.processes_by_exact_name("svchost.exe") {
+    ///     for (name, state) in process.service_states() {
+    ///         println!("{} {} {:?}", process.pid(), name, state);
+    ///     }
+    /// }
+    /// ```
+    pub fn service_states(&self) -> Vec<(String, ServiceState)> {
+        if self.pid.0 == 0 {
+            return Vec::new();
+        }
+        // Stopped services aren't hosted by any process.
+        get_services(SERVICE_ACTIVE)
+            .into_iter()
+            .filter(|service| service.pid as usize == self.pid.0)
+            .map(|service| (service.name, service.state))
+            .collect()
+    }
+
+    /// Returns the exit code of this process if it exited, or `None` if it's still running or if
+    /// the exit code couldn't be read.
+    ///
+    /// `GetExitCodeProcess` returns `STILL_ACTIVE` (`259`) while a process is running, but a
+    /// process can also exit with this code. To tell both cases apart, whether the process object
+    /// is signaled (which happens when it exits) is checked as well.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, ProcessExt, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     process.kill();
+    ///     println!("{:?}", process.exit_code());
+    /// }
+    /// ```
+    pub fn exit_code(&self) -> Option<u32> {
+        if self.handle.is_null() {
+            return None;
+        }
+        unsafe {
+            let mut exit_code = 0;
+            if GetExitCodeProcess(*self.handle, &mut exit_code) == FALSE {
+                return None;
+            }
+            if exit_code != STILL_ACTIVE {
+                return Some(exit_code);
+            }
+        }
+        self.wait_for_exit(0)
+    }
+
+    /// Blocks until this process exits and returns its exit code. Returns `None` if the process
+    /// couldn't be waited for (because of missing permissions for example).
+    ///
+    /// The `Process` can still be used afterwards.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("exited with {:?}", process.wait());
+    /// }
+    /// ```
+    pub fn wait(&self) -> Option<u32> {
+        self.wait_for_exit(INFINITE)
+    }
+
+    /// Blocks until this process exits or until `timeout` elapsed, and returns its exit code.
+    /// Returns `None` if the process is still running after `timeout` or if it couldn't be waited
+    /// for (because of missing permissions for example).
+    ///
+    /// The `Process` can still be used afterwards.
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     match process.wait_timeout(Duration::from_secs(5)) {
+    ///         Some(exit_code) => println!("exited with {}", exit_code),
+    ///         None => println!("still running"),
+    ///     }
+    /// }
+    /// ```
+    pub fn wait_timeout(&self, timeout: Duration) -> Option<u32> {
+        // `INFINITE` is `DWORD::MAX` so longer timeouts are capped just below it.
+        let timeout = std::cmp::min(timeout.as_millis(), (INFINITE - 1) as u128);
+        self.wait_for_exit(timeout as DWORD)
+    }
+
+    /// Returns a future resolving to the exit code of this process once it exits. The wait is
+    /// done by the system thread pool so it doesn't block any thread.
+    ///
+    /// The future resolves to `None` if the process couldn't be opened for waiting (because it
+    /// already exited or because of missing permissions for example), or if it couldn't be
+    /// opened when it was refreshed.
+    ///
+    /// This method is only available with the `async` feature.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     let exited = process.exited();
+    ///     // `exited` can now be awaited from any async runtime.
+    /// }
+    /// ```
+    #[cfg(feature = "async")]
+    pub fn exited(&self) -> impl std::future::Future<Output = Option<u32>> {
+        // The stored handle keeps the process object alive, so the PID can't have been reused by
+        // another process. Without it, the PID could belong to another one.
+        if self.handle.is_null() {
+            return crate::sys::exit::ProcessExit::none();
+        }
+        crate::sys::exit::ProcessExit::new(self.pid)
+    }
+
+    /// Returns the SID, in its string form (like `S-1-5-18`), of the owner of the process
+    /// object, as stored in its security descriptor.
+    ///
+    /// This is not necessarily the user running the process, which is the one of its token.
+    /// Returns `None` if the security descriptor couldn't be read.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{:?}", process.owner_sid());
+    /// }
+    /// ```
+    pub fn owner_sid(&self) -> Option<String> {
+        unsafe {
+            let handle = OpenProcess(READ_CONTROL, FALSE, self.pid.0 as DWORD);
+            if handle.is_null() {
+                return None;
+            }
+            let mut owner: PSID = null_mut();
+            let mut descriptor: PSECURITY_DESCRIPTOR = null_mut();
+            let ret = GetSecurityInfo(
+                handle,
+                SE_KERNEL_OBJECT,
+                OWNER_SECURITY_INFORMATION,
+                &mut owner,
+                null_mut(),
+                null_mut(),
+                null_mut(),
+                &mut descriptor,
+            );
+            CloseHandle(handle);
+            if ret != ERROR_SUCCESS {
+                sysinfo_debug!("GetSecurityInfo failed: {}", ret);
+                return None;
+            }
+            let mut sid_str: LPWSTR = null_mut();
+            let sid = if !owner.is_null() && ConvertSidToStringSidW(owner, &mut sid_str) != FALSE {
+                let sid = String::from_utf16_lossy(std::slice::from_raw_parts(
+                    sid_str,
+                    libc::wcslen(sid_str),
+                ));
+                LocalFree(sid_str as _);
+                Some(sid)
+            } else {
+                None
+            };
+            LocalFree(descriptor as _);
+            sid
+        }
+    }
+
+    /// Returns `true` if one of the top-level windows of this process is visible and isn't
+    /// minimized.
+    ///
+    /// Only the windows of the desktop of the current process are checked.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{}", process.has_visible_window());
+    /// }
+    /// ```
+    pub fn has_visible_window(&self) -> bool {
+        get_process_windows(self.pid)
+            .into_iter()
+            .any(|window| unsafe { IsWindowVisible(window) != FALSE && IsIconic(window) == FALSE })
+    }
+
+    /// Returns `true` if the window which currently has the focus belongs to this process.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{}", process.is_foreground());
+    /// }
+    /// ```
+    pub fn is_foreground(&self) -> bool {
+        get_foreground_process() == Some(self.pid)
+    }
+
+    /// Returns the paths of the .NET assemblies loaded by this process.
+    ///
+    /// This is done on a best-effort basis: the images mapped in the process memory are listed
+    /// and the ones having a CLR header are returned, so assemblies loaded from memory (without a
+    /// file) are missing. An empty list is returned if the process doesn't run the CLR (`clr.dll`,
+    /// `coreclr.dll` or `mscorwks.dll` aren't loaded) or if its memory can't be read.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     for assembly in process.dotnet_assemblies() {
+    ///         println!("{}", assembly);
+    ///     }
+    /// }
+    /// ```
+    pub fn dotnet_assemblies(&self) -> Vec<String> {
+        if self.handle.is_null() {
+            return Vec::new();
+        }
+        unsafe {
+            let runs_clr = get_modules(*self.handle).into_iter().any(|h_mod| {
+                let name = get_process_name(*self.handle, h_mod as _);
+                CLR_MODULES.iter().any(|clr| name.eq_ignore_ascii_case(clr))
+            });
+            if !runs_clr {
+                return Vec::new();
+            }
+            get_mapped_images(*self.handle)
+                .into_iter()
+                .filter(|base| is_managed_image(*self.handle, *base))
+                .filter_map(|base| get_mapped_file_name(*self.handle, base))
+                .collect()
+        }
+    }
+
+    /// Returns the AppUserModelID of this process, which identifies packaged applications (UWP
+    /// or Desktop Bridge apps) for the taskbar, the jump lists and the notifications.
+    ///
+    /// Returns `None` if this process doesn't have an AppUserModelID or if it couldn't be
+    /// retrieved. Always returns `None` before Windows 8.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{:?}", process.app_user_model_id());
+    /// }
+    /// ```
+    pub fn app_user_model_id(&self) -> Option<String> {
+        if self.handle.is_null() {
+            return None;
+        }
+        unsafe { get_app_user_model_id(*self.handle) }
+    }
+
+    /// Returns the command line of this process verbatim, as it was given to it, whereas
+    /// [`ProcessExt::cmd`] returns it split into arguments.
+    ///
+    /// Response files (`@file` arguments) are expanded by the process itself, if it supports
+    /// them, so they are returned as is.
+    ///
+    /// The command line is read every time this method is called. Returns `None` if it couldn't
+    /// be read.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{:?}", process.command_line());
+    /// }
+    /// ```
+    pub fn command_line(&self) -> Option<String> {
+        if self.handle.is_null() {
+            return None;
+        }
+        unsafe { get_raw_cmd_line(*self.handle) }
+    }
+
+    /// Returns a classification of the memory pressure this process is under, based on the
+    /// values retrieved by the last two refreshes:
+    ///
+    /// * [`MemoryPressure::High`] if the process had more than 10,000 page faults since the
+    ///   previous refresh.
+    /// * [`MemoryPressure::Medium`] if it had more than 1,000 page faults, or if its working set
+    ///   grew and is at least 90% of its peak working set.
+    /// * [`MemoryPressure::Low`] otherwise.
+    ///
+    /// The page faults include the soft ones (the page was still in memory), so even the
+    /// `High` level doesn't mean the system is lacking memory.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{:?}", process.memory_pressure());
+    /// }
+    /// ```
+    pub fn memory_pressure(&self) -> MemoryPressure {
+        let page_faults = self.page_faults_delta();
+        if page_faults > HIGH_PRESSURE_PAGE_FAULTS {
+            MemoryPressure::High
+        } else if page_faults > MEDIUM_PRESSURE_PAGE_FAULTS
+            || (self.memory > self.old_memory && self.memory * 10 >= self.peak_memory * 9)
+        {
+            MemoryPressure::Medium
+        } else {
+            MemoryPressure::Low
+        }
+    }
+
+    /// Returns the difference between the address the main image of this process was loaded at
+    /// and its preferred base address, as written in the headers of its executable file.
+    ///
+    /// A delta of `0` for an image supporting ASLR means it wasn't relocated. Returns `None` if
+    /// the base address or the executable file couldn't be read.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{:?}", process.aslr_delta());
+    /// }
+    /// ```
+    pub fn aslr_delta(&self) -> Option<isize> {
+        if self.handle.is_null() {
+            return None;
+        }
+        let mut h_mod = null_mut();
+        unsafe {
+            if !get_h_mod(*self.handle, &mut h_mod) || h_mod.is_null() {
+                return None;
+            }
+        }
+        let preferred_base = get_preferred_image_base(&self.image_path())?;
+        Some((h_mod as usize).wrapping_sub(preferred_base as usize) as isize)
+    }
+
+    /// Returns the handle of the window of the console this process is attached to. It's
+    /// returned as an integer to be cast to `HWND`.
+    ///
+    /// Returns `None` if this process isn't a console application (like GUI applications or
+    /// services) or if its console window couldn't be found, like when it runs in a pseudo
+    /// console.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{:?}", process.console_window());
+    /// }
+    /// ```
+    pub fn console_window(&self) -> Option<usize> {
+        if self.handle.is_null() {
+            return None;
+        }
+        if unsafe { get_image_subsystem(*self.handle) } != Some(IMAGE_SUBSYSTEM_WINDOWS_CUI) {
+            return None;
+        }
+        get_console_window(self.pid).map(|window| window as usize)
+    }
+
+    /// Returns an estimation of the fragmentation of the default heap of this process, between
+    /// `0` and `1`: the part of the memory committed by the heap which isn't used by allocations.
+    ///
+    /// It's only available for the current process: for another process, the system would have
+    /// to create a thread in it to gather the heap information, which could make it deadlock or
+    /// crash. Reading the heap from the memory of the process isn't an option either: the layout
+    /// of its structures isn't documented and changes between Windows versions, the headers of
+    /// the heap blocks are encoded, and the processes using the segment heap (like the UWP
+    /// applications) have a completely different one. Returns `None` for the other processes or
+    /// if the information couldn't be retrieved.
+    ///
+    /// ```no_run
+    /// use sysinfo::{get_current_pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(get_current_pid().unwrap()) {
+    ///     println!("{:?}", process.heap_fragmentation());
+    /// }
+    /// ```
+    pub fn heap_fragmentation(&self) -> Option<f32> {
+        if self.handle.is_null() || self.pid.0 as DWORD != unsafe { GetCurrentProcessId() } {
+            return None;
+        }
+        let (allocated, committed) = unsafe { get_default_heap_usage(self.pid, *self.handle)? };
+        if committed == 0 {
+            return None;
+        }
+        Some(1. - (allocated.min(committed) as f64 / committed as f64) as f32)
+    }
+
+    /// Returns the number of bytes received and sent by this process on its TCP connections,
+    /// since the last refresh and in total, like [`ProcessExt::disk_usage`] does for the disk.
+    ///
+    /// It's only updated when the process is refreshed with
+    /// [`ProcessRefreshKind::network`][crate::ProcessRefreshKind::network] set. The statistics
+    /// of the TCP connections aren't collected by default: only the traffic which happened since
+    /// their collection was enabled (see
+    /// [`set_tcp_statistics_collection`][crate::set_tcp_statistics_collection]) and while the
+    /// process was refreshed is counted. The traffic of the UDP sockets isn't counted.
+    ///
+    /// Returns `None` if the connections couldn't be listed or if none of their statistics could
+    /// be read during the last refresh.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, ProcessRefreshKind, System, SystemExt};
+    ///
+    /// let mut s = System::new();
+    /// s.refresh_process_specifics(Pid::from(1337), ProcessRefreshKind::new().with_network());
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{:?}", process.network_usage());
+    /// }
+    /// ```
+    pub fn network_usage(&self) -> Option<NetworkUsage> {
+        if !self.network_read {
+            return None;
+        }
+        Some(NetworkUsage {
+            rx_bytes: self.network_rx_bytes - self.old_network_rx_bytes,
+            total_rx_bytes: self.network_rx_bytes,
+            tx_bytes: self.network_tx_bytes - self.old_network_tx_bytes,
+            total_tx_bytes: self.network_tx_bytes,
+        })
+    }
+
+    /// Returns `true` if all the threads of this process are suspended, like when it was
+    /// created suspended or when it's frozen by a debugger.
+    ///
+    /// The threads are retrieved every time this method is called. Returns `false` if they
+    /// couldn't be retrieved.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{}", process.is_suspended());
+    /// }
+    /// ```
+    pub fn is_suspended(&self) -> bool {
+        match get_process_threads(self.pid) {
+            Some(threads) => {
+                !threads.is_empty()
+                    && threads.iter().all(|t| {
+                        t.ThreadState == Waiting
+                            && (t.WaitReason == Suspended || t.WaitReason == WrSuspended)
+                    })
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the ID and the priority (as returned by `GetThreadPriority`, like `0` for
+    /// `THREAD_PRIORITY_NORMAL`) of each thread of this process.
+    ///
+    /// The threads are retrieved every time this method is called. Threads which couldn't be
+    /// opened, because they exited in the meantime or because of missing rights, are skipped.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     for (tid, priority) in process.thread_priorities() {
+    ///         println!("{}: {}", tid, priority);
+    ///     }
+    /// }
+    /// ```
+    pub fn thread_priorities(&self) -> Vec<(u32, i32)> {
+        let threads = match get_process_threads(self.pid) {
+            Some(threads) => threads,
+            None => return Vec::new(),
+        };
+        threads
+            .iter()
+            .filter_map(|thread| unsafe {
+                let tid = thread.ClientId.UniqueThread as DWORD;
+                let handle = OpenThread(THREAD_QUERY_INFORMATION, FALSE, tid);
+                if handle.is_null() {
+                    return None;
+                }
+                let priority = GetThreadPriority(handle);
+                CloseHandle(handle);
+                if priority as DWORD == THREAD_PRIORITY_ERROR_RETURN {
+                    sysinfo_debug!("GetThreadPriority failed for thread {}", tid);
+                    return None;
+                }
+                Some((tid, priority))
+            })
+            .collect()
+    }
+
+    /// Returns the CPU usage of this process (in %) divided by the number of logical processors,
+    /// which is `cpu_usage() / nb_processors`. It's the share of the total capacity of the
+    /// machine, so it is always between 0% and 100%.
+    ///
+    /// The two methods use different conventions:
+    ///
+    /// * [`ProcessExt::cpu_usage`] is relative to a single logical processor: a process fully
+    ///   using one logical processor reads 100%, and up to `100 * nb_processors`% when it uses
+    ///   all of them.
+    /// * This method is relative to the whole machine: the same process reads 12.5% on a
+    ///   machine with 8 logical processors, and 100% when it uses all of them.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{}% of the machine", process.cpu_usage_machine_share());
+    /// }
+    /// ```
+    pub fn cpu_usage_machine_share(&self) -> f32 {
+        self.cpu_usage_machine_share
+    }
+
+    /// Stores the current CPU times of this process and of the system without computing a CPU
+    /// usage, so the next refresh of this process computes its CPU usage over the time elapsed
+    /// since this call.
+    ///
+    /// The CPU usage of this process is reset to `0` until then.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, ProcessExt, System, SystemExt};
+    ///
+    /// let mut s = System::new();
+    /// s.refresh_process(Pid::from(1337));
+    /// if let Some(process) = s.process_mut(Pid::from(1337)) {
+    ///     process.prime_cpu();
+    /// }
+    /// std::thread::sleep(std::time::Duration::from_millis(200));
+    /// s.refresh_process(Pid::from(1337));
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{}%", process.cpu_usage());
+    /// }
+    /// ```
+    pub fn prime_cpu(&mut self) {
+        // No usage is computed with `0` processors.
+        compute_cpu_usage(self, 0, &get_system_times());
+        self.cpu_usage = 0.;
+        self.cpu_usage_machine_share = 0.;
+    }
+
+    /// Refreshes the CPU usage of this process every `poll` until it falls below `threshold`
+    /// (in %, like [`ProcessExt::cpu_usage`]). Returns `true` if it did before `timeout`
+    /// elapsed, and `false` otherwise.
+    ///
+    /// The CPU usage is computed over each `poll` interval, so it shouldn't be too short to get
+    /// meaningful values: it's at least 20 ms. A process which exited is considered idle.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    /// use std::time::Duration;
+    ///
+    /// let mut s = System::new();
+    /// s.refresh_process(Pid::from(1337));
+    /// if let Some(process) = s.process_mut(Pid::from(1337)) {
+    ///     if process.wait_until_idle(5., Duration::from_millis(500), Duration::from_secs(30)) {
+    ///         println!("the process finished starting");
+    ///     }
+    /// }
+    /// ```
+    pub fn wait_until_idle(&mut self, threshold: f32, poll: Duration, timeout: Duration) -> bool {
+        if self.handle.is_null() {
+            return false;
+        }
+        let poll = poll.max(MIN_IDLE_POLL_INTERVAL);
+        let start = Instant::now();
+        self.prime_cpu();
+        loop {
+            std::thread::sleep(poll);
+            let old_system_times = (
+                self.cpu_calc_values.old_system_user_cpu,
+                self.cpu_calc_values.old_system_sys_cpu,
+            );
+            compute_cpu_usage(self, *NB_PROCESSORS as u64, &get_system_times());
+            // No CPU usage is computed if the system times didn't change since the last sample.
+            let sampled = old_system_times
+                != (
+                    self.cpu_calc_values.old_system_user_cpu,
+                    self.cpu_calc_values.old_system_sys_cpu,
+                );
+            if sampled && self.cpu_usage < threshold {
+                return true;
+            }
+            if start.elapsed() >= timeout {
+                return false;
+            }
+        }
+    }
+
+    /// Returns `true` if one of the modules (the main image or the DLLs) loaded by this process
+    /// doesn't have a valid Authenticode signature, embedded or through a system catalog.
+    ///
+    /// The signature of every module is checked every time this method is called, which is
+    /// slow. Returns `None` if the modules couldn't be listed or if one of them couldn't be
+    /// opened.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{:?}", process.has_unsigned_modules());
+    /// }
+    /// ```
+    pub fn has_unsigned_modules(&self) -> Option<bool> {
+        if self.handle.is_null() {
+            return None;
+        }
+        let modules = unsafe { get_modules(*self.handle) };
+        if modules.is_empty() {
+            return None;
+        }
+        for h_mod in modules {
+            let path = unsafe { get_module_path(*self.handle, h_mod as _) };
+            if !is_file_signed(&path)? {
+                return Some(true);
+            }
+        }
+        Some(false)
+    }
+
+    /// Returns `true` if this process is attached to a pseudoconsole (ConPTY), like the ones
+    /// created by Windows Terminal or by terminal emulators.
+    ///
+    /// The console host of a pseudoconsole is started with the `--headless` argument, which is
+    /// what is checked. Returns `false` if this process isn't attached to a console or if its
+    /// console host couldn't be read.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{}", process.is_conpty_attached());
+    /// }
+    /// ```
+    pub fn is_conpty_attached(&self) -> bool {
+        if self.handle.is_null() {
+            return false;
+        }
+        unsafe {
+            let host = match get_console_host_pid(*self.handle).and_then(get_process_handler) {
+                Some(host) => host,
+                None => return false,
+            };
+            let headless = get_raw_cmd_line(host)
+                .map(|cmd_line| cmd_line.split_whitespace().any(|arg| arg == "--headless"))
+                .unwrap_or(false);
+            CloseHandle(host);
+            headless
+        }
+    }
+
+    /// Returns the modules (the main image and the DLLs) loaded by this process, the main image
+    /// being the first one.
+    ///
+    /// It's only updated when the process is refreshed with
+    /// [`ProcessRefreshKind::modules`][crate::ProcessRefreshKind::modules] set. The list is empty
+    /// if the modules of the process couldn't be listed, which is the case for protected
+    /// processes or for 64-bit processes when this crate is built for a 32-bit target.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, ProcessRefreshKind, System, SystemExt};
+    ///
+    /// let mut s = System::new();
+    /// s.refresh_process_specifics(Pid::from(1337), ProcessRefreshKind::new().with_modules());
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     for module in process.modules() {
+    ///         println!("{:#x} {}", module.base_address, module.path.display());
+    ///     }
+    /// }
+    /// ```
+    pub fn modules(&self) -> &[ModuleInfo] {
+        &self.modules
+    }
+
+    /// Returns the paths of the modules loaded by this process, in the order they were loaded
+    /// (the main image first, then `ntdll.dll` and so on).
+    ///
+    /// The list is read from the loader data of the process environment block, so modules
+    /// mapped without going through the loader aren't listed. For 32-bit processes running under
+    /// WOW64, the modules of the 32-bit loader are returned. Returns an empty `Vec` if the
+    /// process couldn't be read.
+    ///
+    /// When this crate is built for a 32-bit target, the loader data of 64-bit processes can't be
+    /// read, so an empty `Vec` is returned for them.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     for module in process.modules_in_load_order() {
+    ///         println!("{}", module.display());
+    ///     }
+    /// }
+    /// ```
+    pub fn modules_in_load_order(&self) -> Vec<PathBuf> {
+        if self.handle.is_null() {
+            return Vec::new();
+        }
+        unsafe { get_modules_in_load_order(*self.handle) }
+    }
+
+    /// Returns the total number of bytes read by this process since it started, as counted by
+    /// the system I/O counters (`IO_COUNTERS::ReadTransferCount`).
+    ///
+    /// Unlike [`DiskUsage::read_bytes`][crate::DiskUsage::read_bytes], it isn't relative to the
+    /// previous refresh: it's the same value as
+    /// [`DiskUsage::total_read_bytes`][crate::DiskUsage::total_read_bytes], and it only changes
+    /// when this process is refreshed.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{} B", process.total_read_bytes());
+    /// }
+    /// ```
+    pub fn total_read_bytes(&self) -> u64 {
+        self.read_bytes
+    }
+
+    /// Returns the total number of bytes written by this process since it started, as counted
+    /// by the system I/O counters (`IO_COUNTERS::WriteTransferCount`).
+    ///
+    /// Unlike [`DiskUsage::written_bytes`][crate::DiskUsage::written_bytes], it isn't relative
+    /// to the previous refresh: it's the same value as
+    /// [`DiskUsage::total_written_bytes`][crate::DiskUsage::total_written_bytes], and it only
+    /// changes when this process is refreshed.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{} B", process.total_written_bytes());
+    /// }
+    /// ```
+    pub fn total_written_bytes(&self) -> u64 {
+        self.written_bytes
+    }
+
+    /// Returns the highest amount of physical memory (in KB) this process used since it started,
+    /// known as its peak working set. It's in the same unit as [`ProcessExt::memory`].
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, ProcessExt, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{} KB (peak: {} KB)", process.memory(), process.peak_memory());
+    /// }
+    /// ```
+    pub fn peak_memory(&self) -> u64 {
+        self.peak_memory / 1_000
+    }
+
+    /// Returns the amount of memory (in KB) committed by this process which can be backed by
+    /// the pagefile. It's in the same unit as [`ProcessExt::memory`].
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{} KB", process.pagefile_usage());
+    /// }
+    /// ```
+    pub fn pagefile_usage(&self) -> u64 {
+        self.pagefile_usage / 1_000
+    }
+
+    /// Returns the minimum and maximum working set sizes (in bytes) of this process.
+    ///
+    /// Unless they were made hard limits by the process, the system can trim the working set
+    /// below the minimum or let it grow above the maximum depending on the memory available.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     if let Some((min, max)) = process.working_set_limits() {
+    ///         println!("working set between {} and {} B", min, max);
+    ///     }
+    /// }
+    /// ```
+    pub fn working_set_limits(&self) -> Option<(usize, usize)> {
+        if self.handle.is_null() {
+            return None;
+        }
+        let mut min = 0;
+        let mut max = 0;
+        let mut flags = 0;
+        unsafe {
+            if GetProcessWorkingSetSizeEx(*self.handle, &mut min, &mut max, &mut flags) == FALSE {
+                sysinfo_debug!("GetProcessWorkingSetSizeEx failed on process {}", self.pid);
+                return None;
+            }
+        }
+        Some((min, max))
+    }
+
+    /// Returns the signing level the kernel requires for the images loaded by this process.
+    ///
+    /// For protected processes, it's the level matching the signer of the process. Otherwise,
+    /// it's the one enforced by the binary signature mitigation policy of the process (like
+    /// Microsoft signed images only), or [`SigningLevel::Unchecked`] if there is none. Unlike
+    /// [`Process::has_unsigned_modules`], this is the kernel's verdict and not a check done
+    /// from user mode. Returns `None` if the information couldn't be retrieved.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{:?}", process.signing_level());
+    /// }
+    /// ```
+    pub fn signing_level(&self) -> Option<SigningLevel> {
+        if self.handle.is_null() {
+            return None;
+        }
+        unsafe { get_signing_level(*self.handle) }
+    }
+
+    /// Returns the time when this process was started, in nanoseconds since the UNIX epoch.
+    ///
+    /// Unlike [`ProcessExt::start_time`] which is in seconds, it keeps the full precision of the
+    /// creation time returned by the system (100 nanoseconds), which allows ordering processes
+    /// started at almost the same time. Returns `0` if it couldn't be retrieved.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("Started at {} ns", process.start_time_nanos());
+    /// }
+    /// ```
+    pub fn start_time_nanos(&self) -> u64 {
+        self.start_time_nanos
+    }
+
+    /// Returns the ID of the user running this process, which is the relative ID of the SID of
+    /// its token, like the one of the [`User`][crate::User]s returned by
+    /// [`SystemExt::users`][crate::SystemExt::users].
+    ///
+    /// It's only retrieved when this process is refreshed with
+    /// [`ProcessRefreshKind::user`][crate::ProcessRefreshKind::user] set. Returns `None` if its
+    /// token couldn't be opened, which is the case for the processes of other users when not
+    /// running as administrator.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, ProcessRefreshKind, System, SystemExt};
+    ///
+    /// let mut s = System::new();
+    /// s.refresh_process_specifics(Pid::from(1337), ProcessRefreshKind::new().with_user());
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{:?}", process.user_id());
+    /// }
+    /// ```
+    pub fn user_id(&self) -> Option<&Uid> {
+        self.user_id.as_ref()
+    }
+
+    /// Returns the name (as `DOMAIN\user`) of the user running this process. It's retrieved
+    /// at the same time as [`Process::user_id`].
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, ProcessRefreshKind, System, SystemExt};
+    ///
+    /// let mut s = System::new();
+    /// s.refresh_process_specifics(Pid::from(1337), ProcessRefreshKind::new().with_user());
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{:?}", process.user_name());
+    /// }
+    /// ```
+    pub fn user_name(&self) -> Option<&str> {
+        self.user_name.as_deref()
+    }
+
+    /// Returns `true` if this process is elevated, meaning it runs with the full administrator
+    /// rights of its user (for example after accepting a UAC prompt).
+    ///
+    /// It's only retrieved when the process is refreshed with
+    /// [`ProcessRefreshKind::elevation`][crate::ProcessRefreshKind::elevation] set. Returns
+    /// `None` if its token couldn't be queried.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, ProcessRefreshKind, System, SystemExt};
+    ///
+    /// let mut s = System::new();
+    /// s.refresh_process_specifics(Pid::from(1337), ProcessRefreshKind::new().with_elevation());
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{:?}", process.is_elevated());
+    /// }
+    /// ```
+    pub fn is_elevated(&self) -> Option<bool> {
+        self.elevated
+    }
+
+    /// Returns the integrity level of this process, which is used by Windows to prevent less
+    /// trusted processes from modifying more trusted ones.
+    ///
+    /// It's only retrieved when the process is refreshed with
+    /// [`ProcessRefreshKind::integrity_level`][crate::ProcessRefreshKind::integrity_level] set.
+    /// Returns `None` if its token couldn't be queried.
+    ///
+    /// ```no_run
+    /// use sysinfo::{IntegrityLevel, Pid, ProcessRefreshKind, System, SystemExt};
+    ///
+    /// let mut s = System::new();
+    /// s.refresh_process_specifics(Pid::from(1337), ProcessRefreshKind::new().with_integrity_level());
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     if process.integrity_level() == Some(IntegrityLevel::Low) {
+    ///         println!("sandboxed process");
+    ///     }
+    /// }
+    /// ```
+    pub fn integrity_level(&self) -> Option<IntegrityLevel> {
+        self.integrity_level
+    }
+
+    /// Returns the value of the environment variable `key` of this process. Like on Windows,
+    /// the name of the variable is case-insensitive.
+    ///
+    /// The environment block is only read once, when the process is added to the list, so
+    /// this method doesn't read the memory of the process: it looks up the variables returned
+    /// by [`ProcessExt::environ`]. It's the same as [`ProcessExt::environ_var`], except that the
+    /// value is copied.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{:?}", process.env_var("PATH"));
+    /// }
+    /// ```
+    pub fn env_var(&self, key: &str) -> Option<String> {
+        self.environ_var(key).map(str::to_owned)
+    }
+
+    /// Returns the number of GDI objects (bitmaps, brushes, device contexts, fonts...) currently
+    /// used by this process.
+    ///
+    /// Windows only provides the total number of GDI objects of a process, not their number by
+    /// type. Returns `None` if it couldn't be retrieved.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{:?} GDI objects", process.gdi_objects());
+    /// }
+    /// ```
+    pub fn gdi_objects(&self) -> Option<u32> {
+        get_gui_resources(*self.handle, GR_GDIOBJECTS)
+    }
+
+    /// Returns the highest number of GDI objects used by this process at the same time since it
+    /// started. Compared to [`Process::gdi_objects`], it shows whether the process leaked GDI
+    /// objects at some point. Returns `None` if it couldn't be retrieved.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{:?} GDI objects at most", process.peak_gdi_objects());
+    /// }
+    /// ```
+    pub fn peak_gdi_objects(&self) -> Option<u32> {
+        get_gui_resources(*self.handle, GR_GDIOBJECTS_PEAK)
+    }
+
+    /// Returns `true` if at least one of the threads of this process is impersonating a user,
+    /// which means it's running with the token of this user instead of the one of the process.
+    ///
+    /// A thread impersonating a more privileged user than [`Process::user_name`] (like `SYSTEM`)
+    /// is a common sign of privilege escalation. Returns `None` if none of the threads could be
+    /// checked.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{:?}", process.is_impersonating());
+    /// }
+    /// ```
+    pub fn is_impersonating(&self) -> Option<bool> {
+        Some(!self.impersonated_users()?.is_empty())
+    }
+
+    /// Returns the name (as `DOMAIN\user`) of the user impersonated by a thread of this process
+    /// (see [`Process::is_impersonating`]). If multiple threads are impersonating users, the one
+    /// of the first thread is returned.
+    ///
+    /// Returns `None` if no thread is impersonating a user or if its name couldn't be retrieved.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     if let Some(user) = process.impersonated_user() {
+    ///         println!("impersonating {}", user);
+    ///     }
+    /// }
+    /// ```
+    pub fn impersonated_user(&self) -> Option<String> {
+        self.impersonated_users()?.into_iter().flatten().next()
+    }
+
+    /// Returns the architecture this process runs as: `X86` for WOW64 processes, the native
+    /// architecture of the system otherwise.
+    ///
+    /// It's retrieved with the command line of the process, so it doesn't need an extra call. As
+    /// `ProcessWow64Information` is used, `X64` processes emulated on an `Arm64` system are
+    /// returned as `Arm64` (see [`Process::machine_type`] to get the architecture of the image).
+    /// Returns `None` if the process couldn't be queried.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, ProcessArch, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     if process.architecture() == Some(ProcessArch::X86) {
+    ///         println!("32-bit process");
+    ///     }
+    /// }
+    /// ```
+    pub fn architecture(&self) -> Option<ProcessArch> {
+        self.arch
+    }
+
+    /// Returns the machine type the main image of this process was built for, as written in its
+    /// PE header.
+    ///
+    /// Note that this is not the same thing as the WOW64 status of the process: an `X86` image
+    /// can run on an `X64` or an `Arm64` system, and an `X64` image can run emulated on an
+    /// `Arm64` system. Returns `None` if the image header couldn't be read.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new_all();
+    /// if let Some(process) = s.process(Pid::from(1337)) {
+    ///     println!("{:?}", process.machine_type());
+    /// }
+    /// ```
+    pub fn machine_type(&self) -> Option<MachineType> {
+        if self.handle.is_null() {
+            return None;
+        }
+        unsafe { get_image_file_header(*self.handle) }
+            .map(|header| machine_type_from_raw(header.Machine))
+    }
+
+    // The handle of the process isn't opened with the `PROCESS_SUSPEND_RESUME` right, so a new
+    // one is opened for the call.
+    fn suspend_or_resume(&self, f: unsafe extern "system" fn(HANDLE) -> NTSTATUS) -> bool {
+        if self.handle.is_null() {
+            return false;
+        }
+        unsafe {
+            let handle = OpenProcess(PROCESS_SUSPEND_RESUME, FALSE, self.pid.0 as DWORD);
+            if handle.is_null() {
+                sysinfo_debug!("OpenProcess failed on process {}", self.pid);
+                return false;
+            }
+            let status = f(handle);
+            CloseHandle(handle);
+            if !NT_SUCCESS(status) {
+                sysinfo_debug!(
+                    "failed to suspend or resume process {}: {}",
+                    self.pid,
+                    status
+                );
+                return false;
+            }
+            true
+        }
+    }
+
+    // Asks the process to exit: its windows are closed if it has some, otherwise a Ctrl+Break
+    // event is sent to its console process group.
+    fn terminate_gracefully(&self) -> bool {
+        if close_process_windows(self.pid) {
+            return true;
+        }
+        // The event is sent to a process group of the console of the current process, so it only
+        // reaches this process alone if it leads its own group (which ID is then its PID) on
+        // this console. Otherwise, it would fail or signal other processes.
+        if self.handle.is_null() || !is_attached_to_console(self.pid) {
+            return false;
+        }
+        let pid = self.pid.0 as DWORD;
+        unsafe {
+            if get_process_group_id(*self.handle) != Some(pid) {
+                return false;
+            }
+            // The current process would get the event as well if it's part of the group.
+            if pid != GetCurrentProcessId()
+                && get_process_group_id(GetCurrentProcess()) == Some(pid)
+            {
+                return false;
+            }
+            GenerateConsoleCtrlEvent(CTRL_BREAK_EVENT, pid) != FALSE
+        }
+    }
+
+    // Kills the process with `taskkill`, which can sometimes terminate processes which we aren't
+    // allowed to open directly.
+    fn kill_with_taskkill(&self) -> bool {
+        let mut kill = process::Command::new("taskkill.exe");
+        kill.arg("/PID").arg(self.pid.to_string()).arg("/F");
+        kill.creation_flags(CREATE_NO_WINDOW);
+        match kill.output() {
+            Ok(o) => o.status.success(),
+            Err(_) => false,
+        }
+    }
 
+    // Returns the users impersonated by the threads of this process, which are `None` if their
+    // name couldn't be retrieved. Returns `None` if none of the threads could be checked.
+    fn impersonated_users(&self) -> Option<Vec<Option<String>>> {
+        let threads = get_process_threads(self.pid)?;
+        let mut nb_checked = 0;
+        let mut users = Vec::new();
+        for thread in threads.iter() {
             unsafe {
-                get_h_mod(handle, &mut h_mod);
-                let exe = get_exe(handle, h_mod);
-                let mut root = exe.clone();
-                root.pop();
-                let (cmd, environ, cwd) = match get_process_params(handle) {
-                    Ok(args) => args,
-                    Err(_e) => {
-                        sysinfo_debug!("Failed to get process parameters: {}", _e);
-                        (Vec::new(), Vec::new(), PathBuf::new())
-                    }
-                };
-                let (start_time, run_time) = get_start_and_run_time(handle, now);
-                Process {
-                    handle: PtrWrapper(handle),
-                    name,
-                    pid,
-                    parent,
-                    cmd,
-                    environ,
-                    exe,
-                    cwd,
-                    root,
-                    status: ProcessStatus::Run,
-                    memory,
-                    virtual_memory,
-                    cpu_usage: 0.,
-                    cpu_calc_values: CPUsageCalculationValues::new(),
-                    start_time,
-                    run_time,
-                    updated: true,
-                    old_read_bytes: 0,
-                    old_written_bytes: 0,
-                    read_bytes: 0,
-                    written_bytes: 0,
+                let tid = thread.ClientId.UniqueThread as DWORD;
+                let handle = OpenThread(THREAD_QUERY_INFORMATION, FALSE, tid);
+                if handle.is_null() {
+                    continue;
+                }
+                let mut token = null_mut();
+                // The token is opened with the access rights of the process so that it works
+                // even if the impersonated user isn't allowed to query its own token.
+                let ret = OpenThreadToken(handle, TOKEN_QUERY, TRUE, &mut token);
+                let error = std::io::Error::last_os_error().raw_os_error();
+                CloseHandle(handle);
+                if ret != FALSE {
+                    nb_checked += 1;
+                    users.push(get_token_user(token).map(|(_, name)| name));
+                    CloseHandle(token);
+                } else if error == Some(ERROR_NO_TOKEN as i32) {
+                    // The thread isn't impersonating anyone.
+                    nb_checked += 1;
                 }
-            }
-        } else {
-            Process {
-                handle: PtrWrapper(null_mut()),
-                name,
-                pid,
-                parent,
-                cmd: Vec::new(),
-                environ: Vec::new(),
-                exe: get_executable_path(pid),
-                cwd: PathBuf::new(),
-                root: PathBuf::new(),
-                status: ProcessStatus::Run,
-                memory,
-                virtual_memory,
-                cpu_usage: 0.,
-                cpu_calc_values: CPUsageCalculationValues::new(),
-                start_time: 0,
-                run_time: 0,
-                updated: true,
-                old_read_bytes: 0,
-                old_written_bytes: 0,
-                read_bytes: 0,
-                written_bytes: 0,
             }
         }
+        if nb_checked == 0 {
+            return None;
+        }
+        Some(users)
     }
 
-    fn new_with_handle(
-        pid: Pid,
-        parent: Option<Pid>,
-        process_handler: HANDLE,
-        now: u64,
-    ) -> Process {
-        let mut h_mod = null_mut();
+    // Adds the traffic of the TCP connections of the process since the last refresh to its
+    // network usage. The counters of a connection start again from `0` if it was closed and
+    // another one with the same endpoints was opened in the meantime.
+    fn refresh_network_usage(&mut self, connections: Option<Vec<ConnectionUsage>>) {
+        let connections = match connections {
+            Some(connections) => connections,
+            None => {
+                self.network_read = false;
+                return;
+            }
+        };
+        self.old_network_rx_bytes = self.network_rx_bytes;
+        self.old_network_tx_bytes = self.network_tx_bytes;
+        let mut tcp_connections = HashMap::with_capacity(connections.len());
+        for (id, received, sent) in connections {
+            let (old_received, old_sent) = self.tcp_connections.get(&id).copied().unwrap_or((0, 0));
+            self.network_rx_bytes += received.checked_sub(old_received).unwrap_or(received);
+            self.network_tx_bytes += sent.checked_sub(old_sent).unwrap_or(sent);
+            tcp_connections.insert(id, (received, sent));
+        }
+        // The closed connections are forgotten, their traffic was counted already.
+        self.tcp_connections = tcp_connections;
+        self.network_read = true;
+    }
 
+    // Reads the current working directory again, which is kept as is if reading it failed.
+    fn refresh_cwd(&mut self) {
         unsafe {
-            let name = if get_h_mod(process_handler, &mut h_mod) {
-                get_process_name(process_handler, h_mod)
-            } else {
-                String::new()
+            let handle = *self.handle;
+            let params = match get_wow64_peb(handle) {
+                Ok(pwow32info) => read_process_params(handle, pwow32info),
+                Err(status) => Err(ProcessParamError::from_status(
+                    status,
+                    ProcessParamError::Wow64QueryFailed,
+                )),
             };
+            match params.and_then(|params| params.get_cwd(handle)) {
+                Ok(buffer) => self.cwd = PathBuf::from(null_terminated_wchar_to_string(&buffer)),
+                Err(_e) => sysinfo_debug!("Failed to get the current working directory: {}", _e),
+            }
+        }
+    }
 
-            let exe = get_exe(process_handler, h_mod);
-            let mut root = exe.clone();
-            root.pop();
-            let (cmd, environ, cwd) = match get_process_params(process_handler) {
-                Ok(args) => args,
-                Err(_e) => {
-                    sysinfo_debug!("Failed to get process parameters: {}", _e);
-                    (Vec::new(), Vec::new(), PathBuf::new())
-                }
-            };
-            let (start_time, run_time) = get_start_and_run_time(process_handler, now);
-            Process {
-                handle: PtrWrapper(process_handler),
-                name,
-                pid,
-                parent,
-                cmd,
-                environ,
-                exe,
-                cwd,
-                root,
-                status: ProcessStatus::Run,
-                memory: 0,
-                virtual_memory: 0,
-                cpu_usage: 0.,
-                cpu_calc_values: CPUsageCalculationValues::new(),
-                start_time,
-                run_time,
-                updated: true,
-                old_read_bytes: 0,
-                old_written_bytes: 0,
-                read_bytes: 0,
-                written_bytes: 0,
+    // Waits for at most `timeout` milliseconds for the process to exit and returns its exit code.
+    fn wait_for_exit(&self, timeout: DWORD) -> Option<u32> {
+        if self.handle.is_null() {
+            return None;
+        }
+        unsafe {
+            // Waiting requires `SYNCHRONIZE`, which the stored handle doesn't have. As it keeps
+            // the process object alive, the PID can't have been reused by another process.
+            let handle = OpenProcess(SYNCHRONIZE, FALSE, self.pid.0 as DWORD);
+            if handle.is_null() {
+                return None;
+            }
+            let exited = WaitForSingleObject(handle, timeout) == WAIT_OBJECT_0;
+            CloseHandle(handle);
+            if !exited {
+                return None;
+            }
+            let mut exit_code = 0;
+            if GetExitCodeProcess(*self.handle, &mut exit_code) == FALSE {
+                return None;
             }
+            Some(exit_code)
         }
     }
 
-    pub(crate) fn update(
-        &mut self,
-        refresh_kind: crate::ProcessRefreshKind,
-        nb_processors: u64,
-        now: u64,
-    ) {
-        if refresh_kind.cpu() {
-            compute_cpu_usage(self, nb_processors);
+    // Reads the command line, the environment and the current working directory again. They're
+    // kept as is if reading them failed. The architecture is read too if it's still unknown.
+    fn refresh_params(&mut self) {
+        let (arch, params) = unsafe { get_process_params(*self.handle) };
+        if self.arch.is_none() {
+            self.arch = arch;
         }
-        if refresh_kind.disk_usage() {
-            update_disk_usage(self);
+        match params {
+            Ok((cmd, environ, cwd)) => {
+                self.cmd = cmd;
+                self.environ = environ;
+                self.cwd = cwd;
+                self.params_error = None;
+            }
+            Err(e) => {
+                sysinfo_debug!("Failed to get process parameters: {}", e);
+                self.params_error = Some(e);
+            }
         }
-        self.run_time = now - self.start_time();
-        self.updated = true;
     }
 }
 
 impl ProcessExt for Process {
     fn kill_with(&self, signal: Signal) -> Option<bool> {
-        let _c_signal = super::system::convert_signal(signal)?;
-        let mut kill = process::Command::new("taskkill.exe");
-        kill.arg("/PID").arg(self.pid.to_string()).arg("/F");
-        kill.creation_flags(CREATE_NO_WINDOW);
-        match kill.output() {
-            Ok(o) => Some(o.status.success()),
-            Err(_) => Some(false),
+        let kind = super::system::convert_signal(signal)?;
+        if kind == SignalKind::Graceful {
+            return Some(self.terminate_gracefully());
+        }
+        // The error has to be read before `CloseHandle` is called, which could overwrite it.
+        let (killed, error) = unsafe {
+            let handle = OpenProcess(PROCESS_TERMINATE, FALSE, self.pid.0 as DWORD);
+            if handle.is_null() {
+                (false, std::io::Error::last_os_error().raw_os_error())
+            } else {
+                let killed = TerminateProcess(handle, 1) != FALSE;
+                let error = std::io::Error::last_os_error().raw_os_error();
+                CloseHandle(handle);
+                (killed, error)
+            }
+        };
+        if !killed && error == Some(ERROR_ACCESS_DENIED as i32) {
+            // `taskkill` is only used as a fallback because it requires to spawn a process.
+            return Some(self.kill_with_taskkill());
         }
+        Some(killed)
+    }
+
+    fn suspend(&self) -> bool {
+        self.suspend_or_resume(NtSuspendProcess)
+    }
+
+    fn resume(&self) -> bool {
+        self.suspend_or_resume(NtResumeProcess)
     }
 
     fn name(&self) -> &str {
@@ -393,6 +3849,10 @@ impl ProcessExt for Process {
     }
 
     fn memory(&self) -> u64 {
+        self.memory / 1_000
+    }
+
+    fn memory_bytes(&self) -> u64 {
         self.memory
     }
 
@@ -412,6 +3872,13 @@ impl ProcessExt for Process {
         self.start_time
     }
 
+    fn start_time_system(&self) -> Option<SystemTime> {
+        match self.start_time_nanos {
+            0 => None,
+            start => Some(UNIX_EPOCH + Duration::from_nanos(start)),
+        }
+    }
+
     fn run_time(&self) -> u64 {
         self.run_time
     }
@@ -422,12 +3889,34 @@ impl ProcessExt for Process {
 
     fn disk_usage(&self) -> DiskUsage {
         DiskUsage {
-            written_bytes: self.written_bytes - self.old_written_bytes,
+            // The counters of a process shouldn't decrease but if they get reset (when the
+            // process is created again for example), the usage is `0` instead of underflowing.
+            written_bytes: self.written_bytes.saturating_sub(self.old_written_bytes),
             total_written_bytes: self.written_bytes,
-            read_bytes: self.read_bytes - self.old_read_bytes,
+            read_bytes: self.read_bytes.saturating_sub(self.old_read_bytes),
             total_read_bytes: self.read_bytes,
+            written_operations: self
+                .written_operations
+                .saturating_sub(self.old_written_operations),
+            total_written_operations: self.written_operations,
+            read_operations: self
+                .read_operations
+                .saturating_sub(self.old_read_operations),
+            total_read_operations: self.read_operations,
         }
     }
+
+    fn thread_count(&self) -> usize {
+        self.thread_count
+    }
+
+    fn page_faults(&self) -> u64 {
+        self.page_faults as u64
+    }
+
+    fn page_faults_delta(&self) -> u64 {
+        self.page_faults.wrapping_sub(self.old_page_faults) as u64
+    }
 }
 
 impl Drop for Process {
@@ -441,23 +3930,41 @@ impl Drop for Process {
     }
 }
 
-unsafe fn get_start_and_run_time(handle: HANDLE, now: u64) -> (u64, u64) {
+pub(crate) fn filetime_to_unix_secs(time: u64) -> u64 {
+    // 11_644_473_600 is the number of seconds between the Windows epoch (1601-01-01) and
+    // the linux epoch (1970-01-01).
+    (time / 10_000_000).saturating_sub(11_644_473_600)
+}
+
+fn filetime_to_unix_nanos(time: u64) -> u64 {
+    // A `FILETIME` is a number of 100-nanosecond intervals.
+    time.saturating_sub(11_644_473_600 * 10_000_000)
+        .saturating_mul(100)
+}
+
+// Returns the start time in seconds and in nanoseconds, and the run time in seconds. They are all
+// `0` if the creation time couldn't be retrieved.
+unsafe fn get_start_and_run_time(handle: HANDLE, now: u64) -> (u64, u64, u64) {
     let mut fstart: FILETIME = zeroed();
     let mut x = zeroed();
 
-    GetProcessTimes(
+    if GetProcessTimes(
         handle,
         &mut fstart as *mut FILETIME,
         &mut x as *mut FILETIME,
         &mut x as *mut FILETIME,
         &mut x as *mut FILETIME,
-    );
-    let tmp = super::utils::filetime_to_u64(fstart);
-    // 11_644_473_600 is the number of seconds between the Windows epoch (1601-01-01) and
-    // the linux epoch (1970-01-01).
-    let start = tmp / 10_000_000 - 11_644_473_600;
+    ) == FALSE
+    {
+        return (0, 0, 0);
+    }
+    let fstart = super::utils::filetime_to_u64(fstart);
+    if fstart == 0 {
+        return (0, 0, 0);
+    }
+    let start = filetime_to_unix_secs(fstart);
     let run_time = check_sub(now, start);
-    (start, run_time)
+    (start, filetime_to_unix_nanos(fstart), run_time)
 }
 
 #[allow(clippy::uninit_vec)]
@@ -523,7 +4030,95 @@ unsafe fn get_cmdline_from_buffer(buffer: *const u16) -> Vec<String> {
     res
 }
 
-unsafe fn get_region_size(handle: HANDLE, ptr: LPVOID) -> Result<usize, &'static str> {
+/// Maximum number of bytes read from a process memory for its command line or its environment.
+pub(crate) static PROCESS_DATA_SIZE_LIMIT: AtomicUsize = AtomicUsize::new(4 * 1024 * 1024);
+
+/// Reasons why the parameters (command line, environment...) of a process couldn't be read.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ProcessParamError {
+    /// The process is a 64-bit one while this crate was built for a 32-bit target.
+    Unsupported64On32,
+    /// The handle of the process doesn't have the needed access rights.
+    AccessDenied,
+    /// Checking whether the process runs under WOW64 failed with this `NTSTATUS`.
+    Wow64QueryFailed(NTSTATUS),
+    /// Getting the basic information of the process failed with this `NTSTATUS`.
+    BasicInfoQueryFailed(NTSTATUS),
+    /// Reading the PEB of the process failed with this error code.
+    ReadPebFailed(DWORD),
+    /// Reading the `RTL_USER_PROCESS_PARAMETERS` of the process failed with this error code.
+    ReadParamsFailed(DWORD),
+    /// Querying the memory region of the data failed with this error code.
+    RegionQueryFailed(DWORD),
+    /// Reading the data failed with this error code.
+    ReadDataFailed(DWORD),
+    /// The data is bigger than [`PROCESS_DATA_SIZE_LIMIT`] (its size is kept).
+    DataTooBig(usize),
+}
+
+impl ProcessParamError {
+    fn from_status(status: NTSTATUS, err: fn(NTSTATUS) -> Self) -> Self {
+        if status == STATUS_ACCESS_DENIED {
+            Self::AccessDenied
+        } else {
+            err(status)
+        }
+    }
+
+    fn from_last_error(err: fn(DWORD) -> Self) -> Self {
+        let code = std::io::Error::last_os_error().raw_os_error().unwrap_or(0) as DWORD;
+        if code == ERROR_ACCESS_DENIED {
+            Self::AccessDenied
+        } else {
+            err(code)
+        }
+    }
+
+    /// Returns `true` if reading the parameters again would fail the same way.
+    fn is_permanent(self) -> bool {
+        matches!(self, Self::AccessDenied | Self::Unsupported64On32)
+    }
+}
+
+impl fmt::Display for ProcessParamError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Self::Unsupported64On32 => {
+                f.write_str("64 bit processes can't be read from 32 bit targets")
+            }
+            Self::AccessDenied => f.write_str("Access denied"),
+            Self::Wow64QueryFailed(status) => write!(
+                f,
+                "Unable to check WOW64 information about the process (status: {:#x})",
+                status
+            ),
+            Self::BasicInfoQueryFailed(status) => write!(
+                f,
+                "Unable to get basic process information (status: {:#x})",
+                status
+            ),
+            Self::ReadPebFailed(code) => write!(f, "Unable to read process PEB (error: {})", code),
+            Self::ReadParamsFailed(code) => {
+                write!(f, "Unable to read process parameters (error: {})", code)
+            }
+            Self::RegionQueryFailed(code) => write!(
+                f,
+                "Unable to read process memory information (error: {})",
+                code
+            ),
+            Self::ReadDataFailed(code) => {
+                write!(f, "Unable to read process data (error: {})", code)
+            }
+            Self::DataTooBig(size) => write!(
+                f,
+                "Process data is bigger than the size limit ({} bytes)",
+                size
+            ),
+        }
+    }
+}
+
+unsafe fn get_region_size(handle: HANDLE, ptr: LPVOID) -> Result<usize, ProcessParamError> {
     let mut meminfo = MaybeUninit::<MEMORY_BASIC_INFORMATION>::uninit();
     if VirtualQueryEx(
         handle,
@@ -532,7 +4127,9 @@ unsafe fn get_region_size(handle: HANDLE, ptr: LPVOID) -> Result<usize, &'static
         size_of::<MEMORY_BASIC_INFORMATION>(),
     ) == 0
     {
-        return Err("Unable to read process memory information");
+        return Err(ProcessParamError::from_last_error(
+            ProcessParamError::RegionQueryFailed,
+        ));
     }
     let meminfo = meminfo.assume_init();
     Ok((meminfo.RegionSize as isize - ptr.offset_from(meminfo.BaseAddress)) as usize)
@@ -543,7 +4140,10 @@ unsafe fn get_process_data(
     handle: HANDLE,
     ptr: LPVOID,
     size: usize,
-) -> Result<Vec<u16>, &'static str> {
+) -> Result<Vec<u16>, ProcessParamError> {
+    if size > PROCESS_DATA_SIZE_LIMIT.load(Ordering::Relaxed) {
+        return Err(ProcessParamError::DataTooBig(size));
+    }
     let mut buffer: Vec<u16> = Vec::with_capacity(size / 2 + 1);
     buffer.set_len(size / 2);
     if ReadProcessMemory(
@@ -554,37 +4154,54 @@ unsafe fn get_process_data(
         std::ptr::null_mut(),
     ) != TRUE
     {
-        return Err("Unable to read process data");
+        return Err(ProcessParamError::from_last_error(
+            ProcessParamError::ReadDataFailed,
+        ));
     }
     Ok(buffer)
 }
 
 trait RtlUserProcessParameters {
-    fn get_cmdline(&self, handle: HANDLE) -> Result<Vec<u16>, &'static str>;
-    fn get_cwd(&self, handle: HANDLE) -> Result<Vec<u16>, &'static str>;
-    fn get_environ(&self, handle: HANDLE) -> Result<Vec<u16>, &'static str>;
+    fn get_cmdline(&self, handle: HANDLE) -> Result<Vec<u16>, ProcessParamError>;
+    fn get_cwd(&self, handle: HANDLE) -> Result<Vec<u16>, ProcessParamError>;
+    fn get_environ(&self, handle: HANDLE) -> Result<Vec<u16>, ProcessParamError>;
+    fn get_image_path(&self, handle: HANDLE) -> Result<Vec<u16>, ProcessParamError>;
+    fn process_group_id(&self) -> u32;
 }
 
 macro_rules! impl_RtlUserProcessParameters {
     ($t:ty) => {
         impl RtlUserProcessParameters for $t {
-            fn get_cmdline(&self, handle: HANDLE) -> Result<Vec<u16>, &'static str> {
+            fn get_cmdline(&self, handle: HANDLE) -> Result<Vec<u16>, ProcessParamError> {
                 let ptr = self.CommandLine.Buffer;
                 let size = self.CommandLine.Length;
                 unsafe { get_process_data(handle, ptr as _, size as _) }
             }
-            fn get_cwd(&self, handle: HANDLE) -> Result<Vec<u16>, &'static str> {
+            fn get_cwd(&self, handle: HANDLE) -> Result<Vec<u16>, ProcessParamError> {
                 let ptr = self.CurrentDirectory.DosPath.Buffer;
                 let size = self.CurrentDirectory.DosPath.Length;
                 unsafe { get_process_data(handle, ptr as _, size as _) }
             }
-            fn get_environ(&self, handle: HANDLE) -> Result<Vec<u16>, &'static str> {
+            fn get_environ(&self, handle: HANDLE) -> Result<Vec<u16>, ProcessParamError> {
                 let ptr = self.Environment;
                 unsafe {
-                    let size = get_region_size(handle, ptr as LPVOID)?;
+                    // The environment block doesn't necessarily fill the whole region so it's
+                    // fine to truncate it.
+                    let size = std::cmp::min(
+                        get_region_size(handle, ptr as LPVOID)?,
+                        PROCESS_DATA_SIZE_LIMIT.load(Ordering::Relaxed),
+                    );
                     get_process_data(handle, ptr as _, size as _)
                 }
             }
+            fn get_image_path(&self, handle: HANDLE) -> Result<Vec<u16>, ProcessParamError> {
+                let ptr = self.ImagePathName.Buffer;
+                let size = self.ImagePathName.Length;
+                unsafe { get_process_data(handle, ptr as _, size as _) }
+            }
+            fn process_group_id(&self) -> u32 {
+                self.ProcessGroupId
+            }
         }
     };
 }
@@ -592,14 +4209,62 @@ macro_rules! impl_RtlUserProcessParameters {
 impl_RtlUserProcessParameters!(RTL_USER_PROCESS_PARAMETERS32);
 impl_RtlUserProcessParameters!(RTL_USER_PROCESS_PARAMETERS);
 
+type ProcessParams = (Vec<String>, Vec<String>, PathBuf);
+
+// Returns the architecture of the process along with its parameters: the WOW64 query needed to
+// read the latter tells the former too, which is kept even if the parameters can't be read.
 unsafe fn get_process_params(
     handle: HANDLE,
-) -> Result<(Vec<String>, Vec<String>, PathBuf), &'static str> {
-    if !cfg!(target_pointer_width = "64") {
-        return Err("Non 64 bit targets are not supported");
+) -> (
+    Option<ProcessArch>,
+    Result<ProcessParams, ProcessParamError>,
+) {
+    let pwow32info = match get_wow64_peb(handle) {
+        Ok(pwow32info) => pwow32info,
+        Err(status) => {
+            return (
+                None,
+                Err(ProcessParamError::from_status(
+                    status,
+                    ProcessParamError::Wow64QueryFailed,
+                )),
+            )
+        }
+    };
+    let params = read_process_params(handle, pwow32info).map(|params| {
+        (
+            get_cmd_line(&*params, handle),
+            get_proc_env(&*params, handle),
+            get_cwd(&*params, handle),
+        )
+    });
+    (Some(arch_from_wow64_peb(pwow32info)), params)
+}
+
+static NATIVE_ARCH: Lazy<ProcessArch> = Lazy::new(|| unsafe {
+    let mut info: SYSTEM_INFO = zeroed();
+    GetNativeSystemInfo(&mut info);
+    match info.u.s().wProcessorArchitecture {
+        PROCESSOR_ARCHITECTURE_INTEL => ProcessArch::X86,
+        PROCESSOR_ARCHITECTURE_AMD64 => ProcessArch::X64,
+        PROCESSOR_ARCHITECTURE_ARM64 => ProcessArch::Arm64,
+        _ => ProcessArch::Unknown,
+    }
+});
+
+// `ProcessWow64Information` only tells whether the process runs in the WOW64 emulator, the native
+// architecture of the system is used otherwise.
+fn arch_from_wow64_peb(pwow32info: LPVOID) -> ProcessArch {
+    if pwow32info.is_null() {
+        *NATIVE_ARCH
+    } else {
+        ProcessArch::X86
     }
+}
 
-    // First check if target process is running in wow64 compatibility emulator
+// Returns the address of the 32-bit PEB of the process if it runs in the WOW64 emulator, or a null
+// pointer otherwise.
+unsafe fn get_wow64_peb(handle: HANDLE) -> Result<LPVOID, NTSTATUS> {
     let mut pwow32info = MaybeUninit::<LPVOID>::uninit();
     let result = NtQueryInformationProcess(
         handle,
@@ -609,12 +4274,33 @@ unsafe fn get_process_params(
         null_mut(),
     );
     if !NT_SUCCESS(result) {
-        return Err("Unable to check WOW64 information about the process");
+        return Err(result);
     }
-    let pwow32info = pwow32info.assume_init();
+    Ok(pwow32info.assume_init())
+}
+
+// Returns the ID of the console process group of the process.
+unsafe fn get_process_group_id(handle: HANDLE) -> Option<u32> {
+    let pwow32info = get_wow64_peb(handle).ok()?;
+    let params = read_process_params(handle, pwow32info).ok()?;
+    Some(params.process_group_id())
+}
 
+// `pwow32info` is the address returned by `get_wow64_peb`, which tells if the target process is
+// running in the WOW64 compatibility emulator.
+unsafe fn read_process_params(
+    handle: HANDLE,
+    pwow32info: LPVOID,
+) -> Result<Box<dyn RtlUserProcessParameters>, ProcessParamError> {
     if pwow32info.is_null() {
-        // target is a 64 bit process
+        // On a 32-bit build, the target is a 64-bit process if this one runs in the WOW64
+        // emulator, and its memory can't be read then.
+        if !cfg!(target_pointer_width = "64")
+            && !matches!(get_wow64_peb(GetCurrentProcess()), Ok(peb) if peb.is_null())
+        {
+            return Err(ProcessParamError::Unsupported64On32);
+        }
+        // target has the same bitness as this process, so the native structures can be used
 
         let mut pbasicinfo = MaybeUninit::<PROCESS_BASIC_INFORMATION>::uninit();
         let result = NtQueryInformationProcess(
@@ -625,7 +4311,10 @@ unsafe fn get_process_params(
             null_mut(),
         );
         if !NT_SUCCESS(result) {
-            return Err("Unable to get basic process information");
+            return Err(ProcessParamError::from_status(
+                result,
+                ProcessParamError::BasicInfoQueryFailed,
+            ));
         }
         let pinfo = pbasicinfo.assume_init();
 
@@ -638,7 +4327,9 @@ unsafe fn get_process_params(
             std::ptr::null_mut(),
         ) != TRUE
         {
-            return Err("Unable to read process PEB");
+            return Err(ProcessParamError::from_last_error(
+                ProcessParamError::ReadPebFailed,
+            ));
         }
 
         let peb = peb.assume_init();
@@ -652,15 +4343,12 @@ unsafe fn get_process_params(
             std::ptr::null_mut(),
         ) != TRUE
         {
-            return Err("Unable to read process parameters");
+            return Err(ProcessParamError::from_last_error(
+                ProcessParamError::ReadParamsFailed,
+            ));
         }
 
-        let proc_params = proc_params.assume_init();
-        return Ok((
-            get_cmd_line(&proc_params, handle),
-            get_proc_env(&proc_params, handle),
-            get_cwd(&proc_params, handle),
-        ));
+        return Ok(Box::new(proc_params.assume_init()));
     }
     // target is a 32 bit process in wow64 mode
 
@@ -673,7 +4361,9 @@ unsafe fn get_process_params(
         std::ptr::null_mut(),
     ) != TRUE
     {
-        return Err("Unable to read PEB32");
+        return Err(ProcessParamError::from_last_error(
+            ProcessParamError::ReadPebFailed,
+        ));
     }
     let peb32 = peb32.assume_init();
 
@@ -686,17 +4376,14 @@ unsafe fn get_process_params(
         std::ptr::null_mut(),
     ) != TRUE
     {
-        return Err("Unable to read 32 bit process parameters");
+        return Err(ProcessParamError::from_last_error(
+            ProcessParamError::ReadParamsFailed,
+        ));
     }
-    let proc_params = proc_params.assume_init();
-    Ok((
-        get_cmd_line(&proc_params, handle),
-        get_proc_env(&proc_params, handle),
-        get_cwd(&proc_params, handle),
-    ))
+    Ok(Box::new(proc_params.assume_init()))
 }
 
-fn get_cwd<T: RtlUserProcessParameters>(params: &T, handle: HANDLE) -> PathBuf {
+fn get_cwd<T: RtlUserProcessParameters + ?Sized>(params: &T, handle: HANDLE) -> PathBuf {
     match params.get_cwd(handle) {
         Ok(buffer) => unsafe { PathBuf::from(null_terminated_wchar_to_string(buffer.as_slice())) },
         Err(_e) => {
@@ -715,7 +4402,10 @@ unsafe fn null_terminated_wchar_to_string(slice: &[u16]) -> String {
     }
 }
 
-fn get_cmd_line_old<T: RtlUserProcessParameters>(params: &T, handle: HANDLE) -> Vec<String> {
+fn get_cmd_line_old<T: RtlUserProcessParameters + ?Sized>(
+    params: &T,
+    handle: HANDLE,
+) -> Vec<String> {
     match params.get_cmdline(handle) {
         Ok(buffer) => unsafe { get_cmdline_from_buffer(buffer.as_ptr()) },
         Err(_e) => {
@@ -739,7 +4429,7 @@ fn get_cmd_line_new(handle: HANDLE) -> Vec<String> {
     }
 }
 
-fn get_cmd_line<T: RtlUserProcessParameters>(params: &T, handle: HANDLE) -> Vec<String> {
+fn get_cmd_line<T: RtlUserProcessParameters + ?Sized>(params: &T, handle: HANDLE) -> Vec<String> {
     if *WINDOWS_8_1_OR_NEWER {
         get_cmd_line_new(handle)
     } else {
@@ -747,7 +4437,27 @@ fn get_cmd_line<T: RtlUserProcessParameters>(params: &T, handle: HANDLE) -> Vec<
     }
 }
 
-fn get_proc_env<T: RtlUserProcessParameters>(params: &T, handle: HANDLE) -> Vec<String> {
+// Returns the command line of the process as it was given to it, without splitting it.
+#[allow(clippy::cast_ptr_alignment)]
+unsafe fn get_raw_cmd_line(handle: HANDLE) -> Option<String> {
+    if *WINDOWS_8_1_OR_NEWER {
+        let buffer = ph_query_process_variable_size(handle, ProcessCommandLineInformation)?;
+        let cmd_line = &*(buffer.as_ptr() as *const UNICODE_STRING);
+        if cmd_line.Buffer.is_null() {
+            return None;
+        }
+        Some(String::from_utf16_lossy(std::slice::from_raw_parts(
+            cmd_line.Buffer,
+            cmd_line.Length as usize / size_of::<u16>(),
+        )))
+    } else {
+        let params = read_process_params(handle, get_wow64_peb(handle).ok()?).ok()?;
+        let buffer = params.get_cmdline(handle).ok()?;
+        Some(null_terminated_wchar_to_string(&buffer))
+    }
+}
+
+fn get_proc_env<T: RtlUserProcessParameters + ?Sized>(params: &T, handle: HANDLE) -> Vec<String> {
     match params.get_environ(handle) {
         Ok(buffer) => {
             let equals = "=".encode_utf16().next().unwrap();
@@ -799,90 +4509,137 @@ fn check_sub(a: u64, b: u64) -> u64 {
     }
 }
 
-/// Before changing this function, you must consider the following:
-/// https://github.com/GuillaumeGomez/sysinfo/issues/459
-pub(crate) fn compute_cpu_usage(p: &mut Process, nb_processors: u64) {
+/// Global times of the system, in 100-nanosecond units.
+#[derive(Clone, Copy, Default)]
+pub(crate) struct SystemTimes {
+    pub(crate) idle: u64,
+    // Notice that it includes idle time.
+    pub(crate) kernel: u64,
+    pub(crate) user: u64,
+}
+
+pub(crate) fn get_system_times() -> SystemTimes {
     unsafe {
-        let mut ftime: FILETIME = zeroed();
-        let mut fsys: FILETIME = zeroed();
-        let mut fuser: FILETIME = zeroed();
         let mut fglobal_idle_time: FILETIME = zeroed();
-        let mut fglobal_kernel_time: FILETIME = zeroed(); // notice that it includes idle time
+        let mut fglobal_kernel_time: FILETIME = zeroed();
         let mut fglobal_user_time: FILETIME = zeroed();
 
-        GetProcessTimes(
-            *p.handle,
-            &mut ftime as *mut FILETIME,
-            &mut ftime as *mut FILETIME,
-            &mut fsys as *mut FILETIME,
-            &mut fuser as *mut FILETIME,
-        );
         GetSystemTimes(
             &mut fglobal_idle_time as *mut FILETIME,
             &mut fglobal_kernel_time as *mut FILETIME,
             &mut fglobal_user_time as *mut FILETIME,
         );
+        SystemTimes {
+            idle: super::utils::filetime_to_u64(fglobal_idle_time),
+            kernel: super::utils::filetime_to_u64(fglobal_kernel_time),
+            user: super::utils::filetime_to_u64(fglobal_user_time),
+        }
+    }
+}
 
-        let mut sys: ULARGE_INTEGER = std::mem::zeroed();
-        memcpy(
-            &mut sys as *mut ULARGE_INTEGER as *mut c_void,
-            &mut fsys as *mut FILETIME as *mut c_void,
-            size_of::<FILETIME>(),
-        );
-        let mut user: ULARGE_INTEGER = std::mem::zeroed();
-        memcpy(
-            &mut user as *mut ULARGE_INTEGER as *mut c_void,
-            &mut fuser as *mut FILETIME as *mut c_void,
-            size_of::<FILETIME>(),
-        );
-        let mut global_kernel_time: ULARGE_INTEGER = std::mem::zeroed();
-        memcpy(
-            &mut global_kernel_time as *mut ULARGE_INTEGER as *mut c_void,
-            &mut fglobal_kernel_time as *mut FILETIME as *mut c_void,
-            size_of::<FILETIME>(),
-        );
-        let mut global_user_time: ULARGE_INTEGER = std::mem::zeroed();
-        memcpy(
-            &mut global_user_time as *mut ULARGE_INTEGER as *mut c_void,
-            &mut fglobal_user_time as *mut FILETIME as *mut c_void,
-            size_of::<FILETIME>(),
-        );
+/// Values read in bulk from the `SYSTEM_PROCESS_INFORMATION` entry of a process when all of them
+/// are refreshed, which saves a few syscalls per process.
+pub(crate) struct ProcessSnapshot {
+    // Taken from the snapshot of the TCP tables of all the processes, only if the network usage
+    // is refreshed.
+    pub(crate) tcp_connections: Option<Vec<ConnectionUsage>>,
+    // All the times are in 100-nanosecond units.
+    pub(crate) create_time: u64,
+    pub(crate) kernel_time: u64,
+    pub(crate) user_time: u64,
+    pub(crate) io_counters: IO_COUNTERS,
+    pub(crate) handle_count: u32,
+    pub(crate) session_id: u32,
+}
 
-        let sys = *sys.QuadPart();
-        let user = *user.QuadPart();
-        let global_kernel_time = *global_kernel_time.QuadPart();
-        let global_user_time = *global_user_time.QuadPart();
-
-        let delta_global_kernel_time =
-            check_sub(global_kernel_time, p.cpu_calc_values.old_system_sys_cpu);
-        let delta_global_user_time =
-            check_sub(global_user_time, p.cpu_calc_values.old_system_user_cpu);
-        let delta_user_time = check_sub(user, p.cpu_calc_values.old_process_user_cpu);
-        let delta_sys_time = check_sub(sys, p.cpu_calc_values.old_process_sys_cpu);
-
-        let denominator = (delta_global_user_time + delta_global_kernel_time) as f64;
-
-        p.cpu_usage = 100.0
-            * ((delta_user_time + delta_sys_time) as f64
-                / if denominator == 0.0 {
-                    p.cpu_usage = 0.0;
-                    return;
-                } else {
-                    denominator
-                }) as f32
-            * nb_processors as f32;
-        p.cpu_calc_values.old_process_user_cpu = user;
-        p.cpu_calc_values.old_process_sys_cpu = sys;
-        p.cpu_calc_values.old_system_user_cpu = global_user_time;
-        p.cpu_calc_values.old_system_sys_cpu = global_kernel_time;
+/// Before changing this function, you must consider the following:
+/// https://github.com/GuillaumeGomez/sysinfo/issues/459
+pub(crate) fn compute_cpu_usage(p: &mut Process, nb_processors: u64, system_times: &SystemTimes) {
+    let (sys, user) = unsafe { get_process_cpu_times(*p.handle) };
+    compute_cpu_usage_from_times(p, nb_processors, system_times, sys, user);
+}
+
+// Returns the kernel and user times of the process.
+unsafe fn get_process_cpu_times(handle: HANDLE) -> (u64, u64) {
+    let mut ftime: FILETIME = zeroed();
+    let mut fsys: FILETIME = zeroed();
+    let mut fuser: FILETIME = zeroed();
+
+    GetProcessTimes(
+        handle,
+        &mut ftime as *mut FILETIME,
+        &mut ftime as *mut FILETIME,
+        &mut fsys as *mut FILETIME,
+        &mut fuser as *mut FILETIME,
+    );
+
+    let mut sys: ULARGE_INTEGER = std::mem::zeroed();
+    memcpy(
+        &mut sys as *mut ULARGE_INTEGER as *mut c_void,
+        &mut fsys as *mut FILETIME as *mut c_void,
+        size_of::<FILETIME>(),
+    );
+    let mut user: ULARGE_INTEGER = std::mem::zeroed();
+    memcpy(
+        &mut user as *mut ULARGE_INTEGER as *mut c_void,
+        &mut fuser as *mut FILETIME as *mut c_void,
+        size_of::<FILETIME>(),
+    );
+
+    (*sys.QuadPart(), *user.QuadPart())
+}
+
+fn compute_cpu_usage_from_times(
+    p: &mut Process,
+    nb_processors: u64,
+    system_times: &SystemTimes,
+    sys: u64,
+    user: u64,
+) {
+    p.kernel_time = sys;
+    p.user_time = user;
+    let global_kernel_time = system_times.kernel;
+    let global_user_time = system_times.user;
+
+    let delta_global_kernel_time =
+        check_sub(global_kernel_time, p.cpu_calc_values.old_system_sys_cpu);
+    let delta_global_user_time = check_sub(global_user_time, p.cpu_calc_values.old_system_user_cpu);
+    let delta_user_time = check_sub(user, p.cpu_calc_values.old_process_user_cpu);
+    let delta_sys_time = check_sub(sys, p.cpu_calc_values.old_process_sys_cpu);
+
+    let denominator = (delta_global_user_time + delta_global_kernel_time) as f64;
+    if denominator == 0.0 {
+        p.cpu_usage = 0.0;
+        p.cpu_usage_machine_share = 0.0;
+        return;
     }
+
+    p.cpu_usage_machine_share =
+        100.0 * ((delta_user_time + delta_sys_time) as f64 / denominator) as f32;
+    p.cpu_usage = p.cpu_usage_machine_share * nb_processors as f32;
+    p.cpu_calc_values.old_process_user_cpu = user;
+    p.cpu_calc_values.old_process_sys_cpu = sys;
+    p.cpu_calc_values.old_system_user_cpu = global_user_time;
+    p.cpu_calc_values.old_system_sys_cpu = global_kernel_time;
+}
+
+pub(crate) fn init_cpu_usage_from_snapshot(
+    p: &mut Process,
+    snapshot: &ProcessSnapshot,
+    system_times: &SystemTimes,
+) {
+    p.cpu_calc_values = CPUsageCalculationValues::with_times(
+        snapshot.kernel_time,
+        snapshot.user_time,
+        system_times,
+    );
 }
 
 pub(crate) fn get_handle(p: &Process) -> HANDLE {
     *p.handle
 }
 
-pub(crate) fn update_disk_usage(p: &mut Process) {
+pub(crate) fn update_disk_usage(p: &mut Process, now: u64) {
     let mut counters = MaybeUninit::<IO_COUNTERS>::uninit();
 
     unsafe {
@@ -890,15 +4647,31 @@ pub(crate) fn update_disk_usage(p: &mut Process) {
         if ret == 0 {
             sysinfo_debug!("GetProcessIoCounters call failed on process {}", p.pid());
         } else {
-            let counters = counters.assume_init();
-            p.old_read_bytes = p.read_bytes;
-            p.old_written_bytes = p.written_bytes;
-            p.read_bytes = counters.ReadTransferCount;
-            p.written_bytes = counters.WriteTransferCount;
+            set_io_counters(p, &counters.assume_init(), now);
         }
     }
 }
 
+fn set_io_counters(p: &mut Process, counters: &IO_COUNTERS, now: u64) {
+    // The counters are cumulative since the process started: the previous values are
+    // only kept to compute the usage since the last refresh.
+    p.old_read_bytes = p.read_bytes;
+    p.old_written_bytes = p.written_bytes;
+    p.read_bytes = counters.ReadTransferCount;
+    p.written_bytes = counters.WriteTransferCount;
+    p.old_read_operations = p.read_operations;
+    p.old_written_operations = p.written_operations;
+    p.read_operations = counters.ReadOperationCount;
+    p.written_operations = counters.WriteOperationCount;
+    // The first read of the counters doesn't tell when the I/O happened.
+    if p.io_counters_read
+        && (p.read_bytes != p.old_read_bytes || p.written_bytes != p.old_written_bytes)
+    {
+        p.last_io_time = Some(now);
+    }
+    p.io_counters_read = true;
+}
+
 pub(crate) fn update_memory(p: &mut Process) {
     unsafe {
         let mut pmc: PROCESS_MEMORY_COUNTERS_EX = zeroed();
@@ -909,8 +4682,54 @@ pub(crate) fn update_memory(p: &mut Process) {
             size_of::<PROCESS_MEMORY_COUNTERS_EX>() as DWORD,
         ) != 0
         {
-            p.memory = (pmc.WorkingSetSize as u64) / 1_000;
+            p.old_memory = p.memory;
+            p.memory = pmc.WorkingSetSize as u64;
+            p.peak_memory = pmc.PeakWorkingSetSize as u64;
+            p.pagefile_usage = pmc.PagefileUsage as u64;
+            p.old_page_faults = p.page_faults;
+            p.page_faults = pmc.PageFaultCount;
             p.virtual_memory = (pmc.PrivateUsage as u64) / 1_000;
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{filetime_to_unix_nanos, filetime_to_unix_secs, Process};
+    use crate::{Pid, ProcessExt, ProcessRefreshKind};
+
+    #[test]
+    fn disk_usage_after_counters_reset() {
+        let mut p = Process::new_full(
+            Pid(0),
+            None,
+            0,
+            0,
+            String::new(),
+            0,
+            ProcessRefreshKind::new(),
+        );
+        p.old_written_bytes = 10;
+        p.written_bytes = 5;
+        p.old_read_bytes = 10;
+        p.read_bytes = 5;
+        let usage = p.disk_usage();
+        assert_eq!(usage.written_bytes, 0);
+        assert_eq!(usage.total_written_bytes, 5);
+        assert_eq!(usage.read_bytes, 0);
+        assert_eq!(usage.total_read_bytes, 5);
+    }
+
+    #[test]
+    fn filetime_conversion() {
+        // A zeroed `FILETIME` is before the UNIX epoch and must not wrap around.
+        assert_eq!(filetime_to_unix_secs(0), 0);
+        assert_eq!(filetime_to_unix_nanos(0), 0);
+        assert_eq!(filetime_to_unix_nanos(u64::MAX), u64::MAX);
+
+        let epoch = 11_644_473_600 * 10_000_000;
+        assert_eq!(filetime_to_unix_secs(epoch), 0);
+        assert_eq!(filetime_to_unix_secs(epoch + 15_000_000), 1);
+        assert_eq!(filetime_to_unix_nanos(epoch + 15_000_000), 1_500_000_000);
+    }
+}
@@ -0,0 +1,101 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use std::ptr::null;
+
+use crate::ServiceState;
+
+use winapi::shared::minwindef::{DWORD, FALSE};
+use winapi::shared::winerror::ERROR_MORE_DATA;
+use winapi::um::winnt::SERVICE_WIN32;
+use winapi::um::winsvc::{
+    CloseServiceHandle, EnumServicesStatusExW, OpenSCManagerW, ENUM_SERVICE_STATUS_PROCESSW,
+    SC_ENUM_PROCESS_INFO, SC_MANAGER_ENUMERATE_SERVICE, SERVICE_CONTINUE_PENDING, SERVICE_PAUSED,
+    SERVICE_PAUSE_PENDING, SERVICE_RUNNING, SERVICE_START_PENDING, SERVICE_STOPPED,
+    SERVICE_STOP_PENDING,
+};
+
+pub(crate) struct ServiceEntry {
+    pub(crate) name: String,
+    pub(crate) pid: DWORD,
+    pub(crate) state: ServiceState,
+}
+
+fn service_state_from_raw(state: DWORD) -> ServiceState {
+    match state {
+        SERVICE_STOPPED => ServiceState::Stopped,
+        SERVICE_START_PENDING => ServiceState::StartPending,
+        SERVICE_STOP_PENDING => ServiceState::StopPending,
+        SERVICE_RUNNING => ServiceState::Running,
+        SERVICE_CONTINUE_PENDING => ServiceState::ContinuePending,
+        SERVICE_PAUSE_PENDING => ServiceState::PausePending,
+        SERVICE_PAUSED => ServiceState::Paused,
+        x => ServiceState::Unknown(x),
+    }
+}
+
+unsafe fn wide_to_string(ptr: *const u16) -> String {
+    if ptr.is_null() {
+        return String::new();
+    }
+    let len = libc::wcslen(ptr);
+    String::from_utf16_lossy(std::slice::from_raw_parts(ptr, len))
+}
+
+/// Returns the Win32 services known by the Service Control Manager which are in `state`
+/// (`SERVICE_ACTIVE`, `SERVICE_INACTIVE` or `SERVICE_STATE_ALL`).
+#[allow(clippy::cast_ptr_alignment)]
+pub(crate) fn get_services(state: DWORD) -> Vec<ServiceEntry> {
+    let mut services = Vec::new();
+
+    unsafe {
+        let manager = OpenSCManagerW(null(), null(), SC_MANAGER_ENUMERATE_SERVICE);
+        if manager.is_null() {
+            sysinfo_debug!("OpenSCManagerW failed");
+            return services;
+        }
+        let mut buffer: Vec<u8> = vec![0; 64 * 1024];
+        let mut resume_handle: DWORD = 0;
+
+        loop {
+            let mut needed: DWORD = 0;
+            let mut nb_services: DWORD = 0;
+            let ret = EnumServicesStatusExW(
+                manager,
+                SC_ENUM_PROCESS_INFO,
+                SERVICE_WIN32,
+                state,
+                buffer.as_mut_ptr(),
+                buffer.len() as DWORD,
+                &mut needed,
+                &mut nb_services,
+                &mut resume_handle,
+                null(),
+            );
+            let more_data = ret == FALSE
+                && std::io::Error::last_os_error().raw_os_error() == Some(ERROR_MORE_DATA as i32);
+            if ret == FALSE && !more_data {
+                sysinfo_debug!("EnumServicesStatusExW failed");
+                break;
+            }
+            let entries = std::slice::from_raw_parts(
+                buffer.as_ptr() as *const ENUM_SERVICE_STATUS_PROCESSW,
+                nb_services as usize,
+            );
+            services.extend(entries.iter().map(|entry| ServiceEntry {
+                name: wide_to_string(entry.lpServiceName),
+                pid: entry.ServiceStatusProcess.dwProcessId,
+                // This is the same status as the one returned by `QueryServiceStatusEx`.
+                state: service_state_from_raw(entry.ServiceStatusProcess.dwCurrentState),
+            }));
+            if !more_data {
+                break;
+            }
+            // The enumeration continues from `resume_handle`.
+            if needed as usize > buffer.len() {
+                buffer.resize(needed as usize, 0);
+            }
+        }
+        CloseServiceHandle(manager);
+    }
+    services
+}
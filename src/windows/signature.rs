@@ -0,0 +1,173 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use std::ffi::OsStr;
+use std::mem::{size_of, zeroed};
+use std::os::windows::ffi::OsStrExt;
+use std::path::Path;
+use std::ptr::null_mut;
+
+use winapi::shared::guiddef::GUID;
+use winapi::shared::minwindef::{BOOL, BYTE, DWORD, FALSE, MAX_PATH};
+use winapi::shared::ntdef::{LPCWSTR, PVOID, WCHAR};
+use winapi::um::fileapi::{CreateFileW, OPEN_EXISTING};
+use winapi::um::handleapi::{CloseHandle, INVALID_HANDLE_VALUE};
+use winapi::um::softpub::WINTRUST_ACTION_GENERIC_VERIFY_V2;
+use winapi::um::winnt::{FILE_SHARE_READ, GENERIC_READ, HANDLE};
+use winapi::um::wintrust::{
+    WinVerifyTrust, WINTRUST_DATA, WINTRUST_FILE_INFO, WTD_CHOICE_CATALOG, WTD_CHOICE_FILE,
+    WTD_REVOKE_NONE, WTD_STATEACTION_CLOSE, WTD_STATEACTION_VERIFY, WTD_UI_NONE,
+};
+
+// The catalog APIs aren't provided by `winapi`.
+#[allow(non_snake_case)]
+#[repr(C)]
+struct WINTRUST_CATALOG_INFO {
+    cbStruct: DWORD,
+    dwCatalogVersion: DWORD,
+    pcwszCatalogFilePath: LPCWSTR,
+    pcwszMemberTag: LPCWSTR,
+    pcwszMemberFilePath: LPCWSTR,
+    hMemberFile: HANDLE,
+    pbCalculatedFileHash: *mut BYTE,
+    cbCalculatedFileHash: DWORD,
+    pcCatalogContext: PVOID,
+    hCatAdmin: HANDLE,
+}
+
+#[allow(non_snake_case)]
+#[repr(C)]
+struct CATALOG_INFO {
+    cbStruct: DWORD,
+    wszCatalogFile: [WCHAR; MAX_PATH],
+}
+
+#[link(name = "wintrust")]
+extern "system" {
+    fn CryptCATAdminAcquireContext(
+        phCatAdmin: *mut HANDLE,
+        pgSubsystem: *const GUID,
+        dwFlags: DWORD,
+    ) -> BOOL;
+    fn CryptCATAdminReleaseContext(hCatAdmin: HANDLE, dwFlags: DWORD) -> BOOL;
+    fn CryptCATAdminCalcHashFromFileHandle(
+        hFile: HANDLE,
+        pcbHash: *mut DWORD,
+        pbHash: *mut BYTE,
+        dwFlags: DWORD,
+    ) -> BOOL;
+    fn CryptCATAdminEnumCatalogFromHash(
+        hCatAdmin: HANDLE,
+        pbHash: *mut BYTE,
+        cbHash: DWORD,
+        dwFlags: DWORD,
+        phPrevCatInfo: *mut HANDLE,
+    ) -> HANDLE;
+    fn CryptCATCatalogInfoFromContext(
+        hCatInfo: HANDLE,
+        psCatInfo: *mut CATALOG_INFO,
+        dwFlags: DWORD,
+    ) -> BOOL;
+    fn CryptCATAdminReleaseCatalogContext(
+        hCatAdmin: HANDLE,
+        hCatInfo: HANDLE,
+        dwFlags: DWORD,
+    ) -> BOOL;
+}
+
+unsafe fn verify_trust(union_choice: DWORD, info: PVOID) -> bool {
+    let mut data: WINTRUST_DATA = zeroed();
+    data.cbStruct = size_of::<WINTRUST_DATA>() as DWORD;
+    data.dwUIChoice = WTD_UI_NONE;
+    data.fdwRevocationChecks = WTD_REVOKE_NONE;
+    data.dwUnionChoice = union_choice;
+    // All the members of the union are pointers.
+    *data.u.pFile_mut() = info as *mut WINTRUST_FILE_INFO;
+    data.dwStateAction = WTD_STATEACTION_VERIFY;
+
+    let mut action = WINTRUST_ACTION_GENERIC_VERIFY_V2;
+    let ret = WinVerifyTrust(null_mut(), &mut action, &mut data as *mut _ as PVOID);
+    data.dwStateAction = WTD_STATEACTION_CLOSE;
+    WinVerifyTrust(null_mut(), &mut action, &mut data as *mut _ as PVOID);
+    ret == 0
+}
+
+unsafe fn has_embedded_signature(path: &[u16]) -> bool {
+    let mut info = WINTRUST_FILE_INFO {
+        cbStruct: size_of::<WINTRUST_FILE_INFO>() as DWORD,
+        pcwszFilePath: path.as_ptr(),
+        hFile: null_mut(),
+        pgKnownSubject: null_mut(),
+    };
+    verify_trust(WTD_CHOICE_FILE, &mut info as *mut _ as PVOID)
+}
+
+// Most of the system files aren't signed themselves: their hash is listed in a signed catalog.
+unsafe fn is_signed_by_catalog(path: &[u16], file: HANDLE) -> bool {
+    let mut hash = [0u8; 64];
+    let mut hash_len = hash.len() as DWORD;
+    if CryptCATAdminCalcHashFromFileHandle(file, &mut hash_len, hash.as_mut_ptr(), 0) == FALSE {
+        return false;
+    }
+    let mut cat_admin = null_mut();
+    if CryptCATAdminAcquireContext(&mut cat_admin, null_mut(), 0) == FALSE {
+        return false;
+    }
+    let mut signed = false;
+    let cat_info =
+        CryptCATAdminEnumCatalogFromHash(cat_admin, hash.as_mut_ptr(), hash_len, 0, null_mut());
+    if !cat_info.is_null() {
+        let mut catalog: CATALOG_INFO = zeroed();
+        catalog.cbStruct = size_of::<CATALOG_INFO>() as DWORD;
+        if CryptCATCatalogInfoFromContext(cat_info, &mut catalog, 0) != FALSE {
+            // The members of a catalog are identified by the hexadecimal form of their hash.
+            let tag = hash[..hash_len as usize]
+                .iter()
+                .map(|b| format!("{:02X}", b))
+                .collect::<String>();
+            let tag = to_wide(OsStr::new(&tag));
+            let mut info = WINTRUST_CATALOG_INFO {
+                cbStruct: size_of::<WINTRUST_CATALOG_INFO>() as DWORD,
+                dwCatalogVersion: 0,
+                pcwszCatalogFilePath: catalog.wszCatalogFile.as_ptr(),
+                pcwszMemberTag: tag.as_ptr(),
+                pcwszMemberFilePath: path.as_ptr(),
+                hMemberFile: file,
+                pbCalculatedFileHash: hash.as_mut_ptr(),
+                cbCalculatedFileHash: hash_len,
+                pcCatalogContext: null_mut(),
+                hCatAdmin: cat_admin,
+            };
+            signed = verify_trust(WTD_CHOICE_CATALOG, &mut info as *mut _ as PVOID);
+        }
+        CryptCATAdminReleaseCatalogContext(cat_admin, cat_info, 0);
+    }
+    CryptCATAdminReleaseContext(cat_admin, 0);
+    signed
+}
+
+fn to_wide(s: &OsStr) -> Vec<u16> {
+    s.encode_wide().chain(Some(0)).collect()
+}
+
+/// Returns `true` if the file at `path` has a valid Authenticode signature, either embedded or
+/// through a system catalog. Returns `None` if the file couldn't be opened.
+pub(crate) fn is_file_signed(path: &Path) -> Option<bool> {
+    let path = to_wide(path.as_os_str());
+    unsafe {
+        let file = CreateFileW(
+            path.as_ptr(),
+            GENERIC_READ,
+            FILE_SHARE_READ,
+            null_mut(),
+            OPEN_EXISTING,
+            0,
+            null_mut(),
+        );
+        if file == INVALID_HANDLE_VALUE {
+            return None;
+        }
+        let signed = has_embedded_signature(&path) || is_signed_by_catalog(&path, file);
+        CloseHandle(file);
+        Some(signed)
+    }
+}
@@ -5,11 +5,17 @@ use winapi::um::winreg::HKEY_LOCAL_MACHINE;
 
 use crate::sys::component::{self, Component};
 use crate::sys::disk::Disk;
-use crate::sys::process::{get_handle, update_memory, Process};
+use crate::sys::full_process_info::get_process_threads;
+use crate::sys::handles::get_processes_with_handle_to;
+use crate::sys::process::{
+    filetime_to_unix_secs, get_handle, get_system_times, init_cpu_usage_from_snapshot,
+    update_memory, Process, ProcessSnapshot, SystemTimes,
+};
 use crate::sys::processor::*;
 use crate::sys::tools::*;
 use crate::sys::users::get_users;
 use crate::sys::utils::get_now;
+use crate::sys::window::get_foreground_process;
 
 use crate::utils::into_iter;
 
@@ -19,11 +25,14 @@ use std::ffi::OsStr;
 use std::mem::{size_of, zeroed};
 use std::os::windows::ffi::OsStrExt;
 use std::slice::from_raw_parts;
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 
+use crate::sys::tcp_stats::TcpConnectionsUsage;
 use ntapi::ntexapi::{
     NtQuerySystemInformation, SystemProcessInformation, SYSTEM_PROCESS_INFORMATION,
 };
+use ntapi::ntkeapi::{DeferredReady, Ready};
+use once_cell::sync::Lazy;
 use winapi::ctypes::wchar_t;
 use winapi::shared::minwindef::{DWORD, FALSE, HKEY, LPBYTE, TRUE};
 use winapi::shared::ntdef::{PVOID, ULONG};
@@ -33,15 +42,25 @@ use winapi::um::minwinbase::STILL_ACTIVE;
 use winapi::um::processthreadsapi::GetExitCodeProcess;
 use winapi::um::psapi::{GetPerformanceInfo, PERFORMANCE_INFORMATION};
 use winapi::um::sysinfoapi::{
-    ComputerNamePhysicalDnsHostname, GetComputerNameExW, GetTickCount64, GlobalMemoryStatusEx,
-    MEMORYSTATUSEX,
+    ComputerNamePhysicalDnsHostname, GetComputerNameExW, GetSystemTimeAdjustment, GetTickCount64,
+    GlobalMemoryStatusEx, MEMORYSTATUSEX,
 };
-use winapi::um::winnt::{HANDLE, KEY_READ};
+use winapi::um::winnt::{HANDLE, IO_COUNTERS, KEY_READ};
 use winapi::um::winreg::{RegOpenKeyExW, RegQueryValueExW};
 
+// How a signal is sent to a process.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SignalKind {
+    // The process is killed with `TerminateProcess`.
+    Terminate,
+    // The process is asked to exit (see `Process::terminate_gracefully`).
+    Graceful,
+}
+
 declare_signals! {
-    (),
-    Signal::Kill => (),
+    SignalKind,
+    Signal::Kill => SignalKind::Terminate,
+    Signal::Term => SignalKind::Graceful,
     _ => None,
 }
 
@@ -59,8 +78,14 @@ pub struct System {
     networks: Networks,
     boot_time: u64,
     users: Vec<User>,
+    global_cpu_usage: f32,
+    old_system_times: SystemTimes,
+    cpu_history_len: usize,
 }
 
+// Predicate used to select the processes to refresh.
+type ProcessFilter<'a> = dyn Fn(Pid, &str) -> bool + 'a;
+
 // Useful for parallel iterations.
 struct Wrap<T>(T);
 
@@ -97,6 +122,9 @@ impl SystemExt for System {
             networks: Networks::new(),
             boot_time: unsafe { boot_time() },
             users: Vec::new(),
+            global_cpu_usage: 0.,
+            old_system_times: SystemTimes::default(),
+            cpu_history_len: 0,
         };
         s.refresh_specifics(refreshes);
         s
@@ -188,8 +216,18 @@ impl SystemExt for System {
             return refresh_existing_process(self, pid, refresh_kind);
         }
         let now = get_now();
-        if let Some(mut p) = Process::new_from_pid(pid, now) {
-            p.update(refresh_kind, self.processors.len() as u64, now);
+        if let Some(mut p) = Process::new_from_pid(pid, now, refresh_kind) {
+            if refresh_kind.threads() {
+                p.thread_count = get_thread_count(pid);
+            }
+            p.update(
+                refresh_kind,
+                self.processors.len() as u64,
+                &get_system_times(),
+                self.cpu_history_len,
+                now,
+                None,
+            );
             p.updated = false;
             self.process_list.insert(pid, p);
             true
@@ -198,112 +236,8 @@ impl SystemExt for System {
         }
     }
 
-    #[allow(clippy::cast_ptr_alignment)]
     fn refresh_processes_specifics(&mut self, refresh_kind: ProcessRefreshKind) {
-        // Windows 10 notebook requires at least 512KiB of memory to make it in one go
-        let mut buffer_size: usize = 512 * 1024;
-        let now = get_now();
-
-        loop {
-            let mut process_information: Vec<u8> = Vec::with_capacity(buffer_size);
-            let mut cb_needed = 0;
-
-            unsafe {
-                process_information.set_len(buffer_size);
-                let ntstatus = NtQuerySystemInformation(
-                    SystemProcessInformation,
-                    process_information.as_mut_ptr() as PVOID,
-                    buffer_size as ULONG,
-                    &mut cb_needed,
-                );
-
-                if ntstatus != STATUS_INFO_LENGTH_MISMATCH {
-                    if ntstatus < 0 {
-                        sysinfo_debug!(
-                            "Couldn't get process infos: NtQuerySystemInformation returned {}",
-                            ntstatus
-                        );
-                    }
-
-                    // Parse the data block to get process information
-                    let mut process_ids = Vec::with_capacity(500);
-                    let mut process_information_offset = 0;
-                    loop {
-                        let p = process_information
-                            .as_ptr()
-                            .offset(process_information_offset)
-                            as *const SYSTEM_PROCESS_INFORMATION;
-                        let pi = &*p;
-
-                        process_ids.push(Wrap(p));
-
-                        if pi.NextEntryOffset == 0 {
-                            break;
-                        }
-
-                        process_information_offset += pi.NextEntryOffset as isize;
-                    }
-                    let process_list = Wrap(UnsafeCell::new(&mut self.process_list));
-                    let nb_processors = if refresh_kind.cpu() {
-                        self.processors.len() as u64
-                    } else {
-                        0
-                    };
-
-                    #[cfg(feature = "multithread")]
-                    use rayon::iter::ParallelIterator;
-
-                    // TODO: instead of using parallel iterator only here, would be better to be able
-                    //       to run it over `process_information` directly!
-                    let processes = into_iter(process_ids)
-                        .filter_map(|pi| {
-                            let pi = *pi.0;
-                            let pid = Pid(pi.UniqueProcessId as _);
-                            if let Some(proc_) = (*process_list.0.get()).get_mut(&pid) {
-                                proc_.memory = (pi.WorkingSetSize as u64) / 1_000;
-                                proc_.virtual_memory = (pi.VirtualSize as u64) / 1_000;
-                                proc_.update(refresh_kind, nb_processors, now);
-                                return None;
-                            }
-                            let name = get_process_name(&pi, pid);
-                            let mut p = Process::new_full(
-                                pid,
-                                if pi.InheritedFromUniqueProcessId as usize != 0 {
-                                    Some(Pid(pi.InheritedFromUniqueProcessId as _))
-                                } else {
-                                    None
-                                },
-                                (pi.WorkingSetSize as u64) / 1_000,
-                                (pi.VirtualSize as u64) / 1_000,
-                                name,
-                                now,
-                            );
-                            p.update(refresh_kind, nb_processors, now);
-                            Some(p)
-                        })
-                        .collect::<Vec<_>>();
-                    for p in processes.into_iter() {
-                        self.process_list.insert(p.pid(), p);
-                    }
-                    self.process_list.retain(|_, v| {
-                        let x = v.updated;
-                        v.updated = false;
-                        x
-                    });
-
-                    break;
-                }
-
-                // GetNewBufferSize
-                if cb_needed == 0 {
-                    buffer_size *= 2;
-                    continue;
-                }
-                // allocating a few more kilo bytes just in case there are some new process
-                // kicked in since new call to NtQuerySystemInformation
-                buffer_size = (cb_needed + (1024 * 10)) as usize;
-            }
-        }
+        self.refresh_processes_impl(refresh_kind, None, None);
     }
 
     fn refresh_disks_list(&mut self) {
@@ -454,6 +388,331 @@ impl Default for System {
     }
 }
 
+impl System {
+    /// Returns a mutable reference to the process corresponding to the given `pid`, if any.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let mut s = System::new_all();
+    /// if let Some(process) = s.process_mut(Pid::from(1337)) {
+    ///     process.set_user_data(Box::new(42u32));
+    /// }
+    /// ```
+    pub fn process_mut(&mut self, pid: Pid) -> Option<&mut Process> {
+        self.process_list.get_mut(&pid)
+    }
+
+    /// Returns the CPU usage of the whole system (in %) between the last two refreshes of the
+    /// processes list.
+    ///
+    /// It is computed from the idle and total times of the system, which are retrieved once per
+    /// refresh and also used to compute the CPU usage of each process.
+    ///
+    /// ```no_run
+    /// use sysinfo::{System, SystemExt};
+    ///
+    /// let mut s = System::new_all();
+    /// std::thread::sleep(std::time::Duration::from_millis(500));
+    /// s.refresh_processes();
+    /// println!("{}%", s.global_cpu_usage());
+    /// ```
+    pub fn global_cpu_usage(&self) -> f32 {
+        self.global_cpu_usage
+    }
+
+    /// Sets the number of CPU usage values kept for each process, returned by
+    /// [`Process::cpu_history`]. A new value is added every time the CPU usage of a process is
+    /// refreshed. Setting it to `0` (the default) disables the history.
+    ///
+    /// [`Process::cpu_history`]: crate::Process::cpu_history
+    ///
+    /// ```no_run
+    /// use sysinfo::{System, SystemExt};
+    ///
+    /// let mut s = System::new();
+    /// s.set_cpu_history_len(60);
+    /// ```
+    pub fn set_cpu_history_len(&mut self, len: usize) {
+        self.cpu_history_len = len;
+        for process in self.process_list.values_mut() {
+            process.truncate_cpu_history(len);
+        }
+    }
+
+    /// Returns the PID of the process owning the window which currently has the focus.
+    ///
+    /// Returns `None` if no window has the focus, like when the session is locked.
+    ///
+    /// ```no_run
+    /// use sysinfo::{System, SystemExt};
+    ///
+    /// let s = System::new();
+    /// println!("{:?}", s.foreground_process());
+    /// ```
+    pub fn foreground_process(&self) -> Option<Pid> {
+        get_foreground_process()
+    }
+
+    /// Returns the PIDs of the processes which have a handle opened on the process `target`,
+    /// like debuggers or tools reading its memory. `target` and the current process (which keeps
+    /// handles to the processes it lists) aren't listed.
+    ///
+    /// The system handle table is queried every time this method is called, so it shouldn't be
+    /// called in a hot loop. Without administrator privileges, the processes running with higher
+    /// privileges can't be checked.
+    ///
+    /// ```no_run
+    /// use sysinfo::{Pid, System, SystemExt};
+    ///
+    /// let s = System::new();
+    /// for pid in s.processes_with_handle_to(Pid::from(1337)) {
+    ///     println!("{}", pid);
+    /// }
+    /// ```
+    pub fn processes_with_handle_to(&self, target: Pid) -> Vec<Pid> {
+        get_processes_with_handle_to(target)
+    }
+
+    /// Gets the first `max` processes of the processes list and updates their information. The
+    /// other ones are removed from the list.
+    ///
+    /// It allows to bound the cost of a refresh on systems with a lot of processes. The order of
+    /// the processes is the one returned by the system, which is mostly by creation time.
+    ///
+    /// ```no_run
+    /// use sysinfo::{ProcessRefreshKind, System, SystemExt};
+    ///
+    /// let mut s = System::new();
+    /// s.refresh_processes_limited(100, ProcessRefreshKind::everything());
+    /// assert!(s.processes().len() <= 100);
+    /// ```
+    pub fn refresh_processes_limited(&mut self, max: usize, refresh_kind: ProcessRefreshKind) {
+        self.refresh_processes_impl(refresh_kind, Some(max), None);
+    }
+
+    /// Gets the processes list and updates the information of the processes for which
+    /// `predicate` returns `true`. The other ones are removed from the list.
+    ///
+    /// `predicate` receives the PID and the name of every process, as returned by the
+    /// enumeration of the processes, before the processes are opened. So filtering out the
+    /// processes you're not interested in makes the refresh much cheaper.
+    ///
+    /// ```no_run
+    /// use sysinfo::{ProcessRefreshKind, System, SystemExt};
+    ///
+    /// let mut s = System::new();
+    /// s.refresh_processes_filtered(
+    ///     |_pid, name| name.eq_ignore_ascii_case("svchost.exe"),
+    ///     ProcessRefreshKind::everything(),
+    /// );
+    /// ```
+    pub fn refresh_processes_filtered<F: Fn(Pid, &str) -> bool>(
+        &mut self,
+        predicate: F,
+        refresh_kind: ProcessRefreshKind,
+    ) {
+        self.refresh_processes_impl(refresh_kind, None, Some(&predicate));
+    }
+
+    #[allow(clippy::cast_ptr_alignment)]
+    fn refresh_processes_impl(
+        &mut self,
+        refresh_kind: ProcessRefreshKind,
+        max: Option<usize>,
+        filter: Option<&ProcessFilter<'_>>,
+    ) {
+        // Windows 10 notebook requires at least 512KiB of memory to make it in one go
+        let mut buffer_size: usize = 512 * 1024;
+        let now = get_now();
+        let system_times = get_system_times();
+        self.update_global_cpu_usage(&system_times);
+
+        loop {
+            let mut process_information: Vec<u8> = Vec::with_capacity(buffer_size);
+            let mut cb_needed = 0;
+
+            unsafe {
+                process_information.set_len(buffer_size);
+                let ntstatus = NtQuerySystemInformation(
+                    SystemProcessInformation,
+                    process_information.as_mut_ptr() as PVOID,
+                    buffer_size as ULONG,
+                    &mut cb_needed,
+                );
+
+                if ntstatus != STATUS_INFO_LENGTH_MISMATCH {
+                    if ntstatus < 0 {
+                        sysinfo_debug!(
+                            "Couldn't get process infos: NtQuerySystemInformation returned {}",
+                            ntstatus
+                        );
+                    }
+
+                    // Parse the data block to get process information
+                    let mut process_ids = Vec::with_capacity(500);
+                    let mut process_information_offset = 0;
+                    loop {
+                        let p = process_information
+                            .as_ptr()
+                            .offset(process_information_offset)
+                            as *const SYSTEM_PROCESS_INFORMATION;
+                        let pi = &*p;
+
+                        // The name is kept so it's not computed again for the new processes.
+                        match filter {
+                            Some(filter) => {
+                                let pid = Pid(pi.UniqueProcessId as _);
+                                let name = get_process_name(pi, pid);
+                                if filter(pid, &name) {
+                                    process_ids.push((Wrap(p), Some(name)));
+                                }
+                            }
+                            None => process_ids.push((Wrap(p), None)),
+                        }
+
+                        if pi.NextEntryOffset == 0 {
+                            break;
+                        }
+
+                        process_information_offset += pi.NextEntryOffset as isize;
+                    }
+                    if let Some(max) = max {
+                        process_ids.truncate(max);
+                    }
+                    // The TCP tables are read once for all the processes.
+                    let tcp_connections = if refresh_kind.network() {
+                        let pids = process_ids
+                            .iter()
+                            .map(|(pi, _)| (*pi.0).UniqueProcessId as DWORD)
+                            .collect();
+                        Some(TcpConnectionsUsage::new(&pids))
+                    } else {
+                        None
+                    };
+                    let cpu_history_len = self.cpu_history_len;
+                    let process_list = Wrap(UnsafeCell::new(&mut self.process_list));
+                    let nb_processors = if refresh_kind.cpu() {
+                        self.processors.len() as u64
+                    } else {
+                        0
+                    };
+
+                    #[cfg(feature = "multithread")]
+                    use rayon::iter::ParallelIterator;
+
+                    // TODO: instead of using parallel iterator only here, would be better to be able
+                    //       to run it over `process_information` directly!
+                    let processes = into_iter(process_ids)
+                        .filter_map(|(pi, name)| {
+                            // The threads information follows the process one so it needs to be
+                            // read before copying it.
+                            let ready_time = get_ready_time(&*pi.0);
+                            let pi = *pi.0;
+                            let pid = Pid(pi.UniqueProcessId as _);
+                            let snapshot = get_process_snapshot(&pi, tcp_connections.as_ref());
+                            let start_time = filetime_to_unix_secs(snapshot.create_time);
+                            if let Some(proc_) = (*process_list.0.get()).get_mut(&pid) {
+                                // If the start time changed, the PID was reused by another
+                                // process so the old entry is replaced.
+                                if proc_.start_time() == 0
+                                    || start_time == 0
+                                    || proc_.start_time() == start_time
+                                {
+                                    proc_.old_memory = proc_.memory;
+                                    proc_.memory = pi.WorkingSetSize as u64;
+                                    proc_.peak_memory = pi.PeakWorkingSetSize as u64;
+                                    proc_.pagefile_usage = pi.PagefileUsage as u64;
+                                    proc_.old_page_faults = proc_.page_faults;
+                                    proc_.page_faults = pi.PageFaultCount;
+                                    proc_.virtual_memory = (pi.VirtualSize as u64) / 1_000;
+                                    proc_.ready_time = Some(ready_time);
+                                    if refresh_kind.threads() {
+                                        proc_.thread_count = pi.NumberOfThreads as usize;
+                                    }
+                                    proc_.update(
+                                        refresh_kind,
+                                        nb_processors,
+                                        &system_times,
+                                        cpu_history_len,
+                                        now,
+                                        Some(&snapshot),
+                                    );
+                                    return None;
+                                }
+                            }
+                            let name = name.unwrap_or_else(|| get_process_name(&pi, pid));
+                            let mut p = Process::new_full(
+                                pid,
+                                if pi.InheritedFromUniqueProcessId as usize != 0 {
+                                    Some(Pid(pi.InheritedFromUniqueProcessId as _))
+                                } else {
+                                    None
+                                },
+                                pi.WorkingSetSize as u64,
+                                (pi.VirtualSize as u64) / 1_000,
+                                name,
+                                now,
+                                refresh_kind,
+                            );
+                            p.ready_time = Some(ready_time);
+                            if refresh_kind.threads() {
+                                p.thread_count = pi.NumberOfThreads as usize;
+                            }
+                            p.peak_memory = pi.PeakWorkingSetSize as u64;
+                            p.pagefile_usage = pi.PagefileUsage as u64;
+                            p.page_faults = pi.PageFaultCount;
+                            p.old_page_faults = pi.PageFaultCount;
+                            init_cpu_usage_from_snapshot(&mut p, &snapshot, &system_times);
+                            p.update(
+                                refresh_kind,
+                                nb_processors,
+                                &system_times,
+                                cpu_history_len,
+                                now,
+                                Some(&snapshot),
+                            );
+                            Some(p)
+                        })
+                        .collect::<Vec<_>>();
+                    for p in processes.into_iter() {
+                        self.process_list.insert(p.pid(), p);
+                    }
+                    self.process_list.retain(|_, v| {
+                        let x = v.updated;
+                        v.updated = false;
+                        x
+                    });
+
+                    break;
+                }
+
+                // GetNewBufferSize
+                if cb_needed == 0 {
+                    buffer_size *= 2;
+                    continue;
+                }
+                // allocating a few more kilo bytes just in case there are some new process
+                // kicked in since new call to NtQuerySystemInformation
+                buffer_size = (cb_needed + (1024 * 10)) as usize;
+            }
+        }
+    }
+
+    fn update_global_cpu_usage(&mut self, system_times: &SystemTimes) {
+        let old = &self.old_system_times;
+        let delta_idle = system_times.idle.saturating_sub(old.idle);
+        let delta_total =
+            (system_times.kernel + system_times.user).saturating_sub(old.kernel + old.user);
+        self.global_cpu_usage = if delta_total == 0 {
+            0.
+        } else {
+            100. * (delta_total.saturating_sub(delta_idle) as f64 / delta_total as f64) as f32
+        };
+        self.old_system_times = *system_times;
+    }
+}
+
 fn is_proc_running(handle: HANDLE) -> bool {
     let mut exit_code = 0;
     unsafe {
@@ -468,7 +727,17 @@ fn refresh_existing_process(s: &mut System, pid: Pid, refresh_kind: ProcessRefre
             return false;
         }
         update_memory(entry);
-        entry.update(refresh_kind, s.processors.len() as u64, get_now());
+        if refresh_kind.threads() {
+            entry.thread_count = get_thread_count(pid);
+        }
+        entry.update(
+            refresh_kind,
+            s.processors.len() as u64,
+            &get_system_times(),
+            s.cpu_history_len,
+            get_now(),
+            None,
+        );
         entry.updated = false;
         true
     } else {
@@ -476,6 +745,14 @@ fn refresh_existing_process(s: &mut System, pid: Pid, refresh_kind: ProcessRefre
     }
 }
 
+// Returns `0` if the process couldn't be found.
+fn get_thread_count(pid: Pid) -> usize {
+    match get_process_threads(pid) {
+        Some(threads) => threads.len(),
+        None => 0,
+    }
+}
+
 #[allow(clippy::size_of_in_element_count)]
 //^ needed for "name.Length as usize / std::mem::size_of::<u16>()"
 pub(crate) fn get_process_name(process: &SYSTEM_PROCESS_INFORMATION, process_id: Pid) -> String {
@@ -499,6 +776,55 @@ pub(crate) fn get_process_name(process: &SYSTEM_PROCESS_INFORMATION, process_id:
     }
 }
 
+// Duration of a clock tick, in 100-nanosecond units.
+static TIME_INCREMENT: Lazy<u64> = Lazy::new(|| unsafe {
+    let mut adjustment = 0;
+    let mut increment = 0;
+    let mut disabled = 0;
+    if GetSystemTimeAdjustment(&mut adjustment, &mut increment, &mut disabled) == FALSE {
+        sysinfo_debug!("GetSystemTimeAdjustment failed");
+        // Default clock interval.
+        return 156_250;
+    }
+    increment as u64
+});
+
+/// Sums for how long the ready threads of `process` have been waiting to be scheduled.
+unsafe fn get_ready_time(process: &SYSTEM_PROCESS_INFORMATION) -> Duration {
+    let threads = from_raw_parts(process.Threads.as_ptr(), process.NumberOfThreads as usize);
+    let ticks: u64 = threads
+        .iter()
+        .filter(|t| t.ThreadState == Ready || t.ThreadState == DeferredReady)
+        .map(|t| t.WaitTime as u64)
+        .sum();
+    Duration::from_nanos(ticks * *TIME_INCREMENT * 100)
+}
+
+fn get_process_snapshot(
+    process: &SYSTEM_PROCESS_INFORMATION,
+    tcp_connections: Option<&TcpConnectionsUsage>,
+) -> ProcessSnapshot {
+    unsafe {
+        ProcessSnapshot {
+            tcp_connections: tcp_connections
+                .and_then(|usage| usage.get(Pid(process.UniqueProcessId as _))),
+            create_time: *process.CreateTime.QuadPart() as u64,
+            kernel_time: *process.KernelTime.QuadPart() as u64,
+            user_time: *process.UserTime.QuadPart() as u64,
+            io_counters: IO_COUNTERS {
+                ReadOperationCount: *process.ReadOperationCount.QuadPart() as u64,
+                WriteOperationCount: *process.WriteOperationCount.QuadPart() as u64,
+                OtherOperationCount: *process.OtherOperationCount.QuadPart() as u64,
+                ReadTransferCount: *process.ReadTransferCount.QuadPart() as u64,
+                WriteTransferCount: *process.WriteTransferCount.QuadPart() as u64,
+                OtherTransferCount: *process.OtherTransferCount.QuadPart() as u64,
+            },
+            handle_count: process.HandleCount,
+            session_id: process.SessionId,
+        }
+    }
+}
+
 fn utf16_str<S: AsRef<OsStr> + ?Sized>(text: &S) -> Vec<u16> {
     OsStr::new(text)
         .encode_wide()
@@ -548,7 +874,7 @@ fn get_reg_string_value(hkey: HKEY, path: &str, field_name: &str) -> Option<Stri
     }
 }
 
-fn get_reg_value_u32(hkey: HKEY, path: &str, field_name: &str) -> Option<[u8; 4]> {
+pub(crate) fn get_reg_value_u32(hkey: HKEY, path: &str, field_name: &str) -> Option<[u8; 4]> {
     let c_path = utf16_str(path);
     let c_field_name = utf16_str(field_name);
 
@@ -0,0 +1,247 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use crate::Pid;
+
+use std::collections::{HashMap, HashSet};
+use std::mem::{size_of, zeroed};
+use std::ptr::null_mut;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use winapi::shared::iprtrmib::TCP_TABLE_OWNER_PID_CONNECTIONS;
+use winapi::shared::minwindef::{DWORD, FALSE, TRUE, ULONG};
+use winapi::shared::ntdef::{PUCHAR, PVOID};
+use winapi::shared::tcpestats::{
+    TCP_ESTATS_DATA_ROD_v0, TCP_ESTATS_DATA_RW_v0, TcpConnectionEstatsData, TCP_ESTATS_TYPE,
+};
+use winapi::shared::tcpmib::{
+    MIB_TCP6ROW, MIB_TCP6TABLE_OWNER_PID, MIB_TCPROW, MIB_TCPTABLE_OWNER_PID,
+};
+use winapi::shared::winerror::{ERROR_INSUFFICIENT_BUFFER, NO_ERROR};
+use winapi::shared::ws2def::{AF_INET, AF_INET6};
+use winapi::um::iphlpapi::{
+    GetExtendedTcpTable, GetPerTcp6ConnectionEStats, GetPerTcpConnectionEStats,
+    SetPerTcp6ConnectionEStats, SetPerTcpConnectionEStats,
+};
+
+type SetEStatsFn<R> =
+    unsafe extern "system" fn(*mut R, TCP_ESTATS_TYPE, PUCHAR, ULONG, ULONG, ULONG) -> ULONG;
+type GetEStatsFn<R> = unsafe extern "system" fn(
+    *mut R,
+    TCP_ESTATS_TYPE,
+    PUCHAR,
+    ULONG,
+    ULONG,
+    PUCHAR,
+    ULONG,
+    ULONG,
+    PUCHAR,
+    ULONG,
+    ULONG,
+) -> ULONG;
+
+/// Whether the collection of the statistics of the TCP connections is enabled when they're read.
+pub(crate) static TCP_STATISTICS_COLLECTION: AtomicBool = AtomicBool::new(false);
+
+/// Identifies a TCP connection by its endpoints. IPv4 addresses only use the first 4 bytes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct ConnectionId {
+    local_addr: [u8; 16],
+    local_port: DWORD,
+    remote_addr: [u8; 16],
+    remote_port: DWORD,
+}
+
+impl ConnectionId {
+    fn new(local_addr: &[u8], local_port: DWORD, remote_addr: &[u8], remote_port: DWORD) -> Self {
+        let mut id = ConnectionId {
+            local_addr: [0; 16],
+            local_port,
+            remote_addr: [0; 16],
+            remote_port,
+        };
+        id.local_addr[..local_addr.len()].copy_from_slice(local_addr);
+        id.remote_addr[..remote_addr.len()].copy_from_slice(remote_addr);
+        id
+    }
+}
+
+// Returns the table of the TCP connections (not the listening sockets) of the given family.
+unsafe fn get_tcp_table(family: ULONG) -> Option<Vec<u8>> {
+    let mut size: DWORD = 0;
+
+    loop {
+        let mut buffer: Vec<u8> = vec![0; size as usize];
+        let ret = GetExtendedTcpTable(
+            buffer.as_mut_ptr() as PVOID,
+            &mut size,
+            FALSE,
+            family,
+            TCP_TABLE_OWNER_PID_CONNECTIONS,
+            0,
+        );
+        match ret {
+            NO_ERROR => return Some(buffer),
+            // Connections might have been opened in the meantime.
+            ERROR_INSUFFICIENT_BUFFER => continue,
+            _ => {
+                sysinfo_debug!("GetExtendedTcpTable failed: {}", ret);
+                return None;
+            }
+        }
+    }
+}
+
+// Returns the number of bytes received and sent on the connection `row`.
+unsafe fn get_connection_usage<R>(
+    row: &mut R,
+    set: SetEStatsFn<R>,
+    get: GetEStatsFn<R>,
+) -> Option<(u64, u64)> {
+    // The collection is disabled by default and enabling it requires administrator privileges.
+    // It might have already been enabled though, so the result is ignored.
+    if TCP_STATISTICS_COLLECTION.load(Ordering::Relaxed) {
+        let mut rw = TCP_ESTATS_DATA_RW_v0 {
+            EnableCollection: TRUE as _,
+        };
+        set(
+            row,
+            TcpConnectionEstatsData,
+            &mut rw as *mut _ as PUCHAR,
+            0,
+            size_of::<TCP_ESTATS_DATA_RW_v0>() as ULONG,
+            0,
+        );
+    }
+    let mut rod: TCP_ESTATS_DATA_ROD_v0 = zeroed();
+    let ret = get(
+        row,
+        TcpConnectionEstatsData,
+        null_mut(),
+        0,
+        0,
+        null_mut(),
+        0,
+        0,
+        &mut rod as *mut _ as PUCHAR,
+        0,
+        size_of::<TCP_ESTATS_DATA_ROD_v0>() as ULONG,
+    );
+    if ret != NO_ERROR {
+        return None;
+    }
+    Some((rod.DataBytesIn, rod.DataBytesOut))
+}
+
+/// Number of bytes received and sent on a TCP connection.
+pub(crate) type ConnectionUsage = (ConnectionId, u64, u64);
+
+// Number of TCP connections of a process and usage of the ones which statistics could be read.
+type ProcessConnections = (usize, Vec<ConnectionUsage>);
+
+// Reads the TCP tables once and returns the connections of the processes for which `filter`
+// returns `true`, by PID.
+#[allow(clippy::cast_ptr_alignment)]
+unsafe fn read_tcp_connections_usage<F: Fn(DWORD) -> bool>(
+    filter: F,
+) -> Option<HashMap<DWORD, ProcessConnections>> {
+    let mut connections: HashMap<DWORD, ProcessConnections> = HashMap::new();
+
+    let buffer = get_tcp_table(AF_INET as ULONG)?;
+    let table = &*(buffer.as_ptr() as *const MIB_TCPTABLE_OWNER_PID);
+    let rows = std::slice::from_raw_parts(table.table.as_ptr(), table.dwNumEntries as usize);
+    for entry in rows.iter().filter(|entry| filter(entry.dwOwningPid)) {
+        let (nb_connections, usage) = connections.entry(entry.dwOwningPid).or_default();
+        *nb_connections += 1;
+        let mut row = MIB_TCPROW {
+            State: entry.dwState,
+            dwLocalAddr: entry.dwLocalAddr,
+            dwLocalPort: entry.dwLocalPort,
+            dwRemoteAddr: entry.dwRemoteAddr,
+            dwRemotePort: entry.dwRemotePort,
+        };
+        if let Some((received, sent)) = get_connection_usage(
+            &mut row,
+            SetPerTcpConnectionEStats,
+            GetPerTcpConnectionEStats,
+        ) {
+            let id = ConnectionId::new(
+                &entry.dwLocalAddr.to_ne_bytes(),
+                entry.dwLocalPort,
+                &entry.dwRemoteAddr.to_ne_bytes(),
+                entry.dwRemotePort,
+            );
+            usage.push((id, received, sent));
+        }
+    }
+
+    let buffer = get_tcp_table(AF_INET6 as ULONG)?;
+    let table = &*(buffer.as_ptr() as *const MIB_TCP6TABLE_OWNER_PID);
+    let rows = std::slice::from_raw_parts(table.table.as_ptr(), table.dwNumEntries as usize);
+    for entry in rows.iter().filter(|entry| filter(entry.dwOwningPid)) {
+        let (nb_connections, usage) = connections.entry(entry.dwOwningPid).or_default();
+        *nb_connections += 1;
+        let mut row: MIB_TCP6ROW = zeroed();
+        row.State = entry.dwState;
+        *row.LocalAddr.u.Byte_mut() = entry.ucLocalAddr;
+        row.dwLocalScopeId = entry.dwLocalScopeId;
+        row.dwLocalPort = entry.dwLocalPort;
+        *row.RemoteAddr.u.Byte_mut() = entry.ucRemoteAddr;
+        row.dwRemoteScopeId = entry.dwRemoteScopeId;
+        row.dwRemotePort = entry.dwRemotePort;
+        if let Some((received, sent)) = get_connection_usage(
+            &mut row,
+            SetPerTcp6ConnectionEStats,
+            GetPerTcp6ConnectionEStats,
+        ) {
+            let id = ConnectionId::new(
+                &entry.ucLocalAddr,
+                entry.dwLocalPort,
+                &entry.ucRemoteAddr,
+                entry.dwRemotePort,
+            );
+            usage.push((id, received, sent));
+        }
+    }
+    Some(connections)
+}
+
+// The connections which statistics couldn't be read are skipped. `None` is returned if the
+// process has connections but none of them could be read.
+fn into_usage((nb_connections, usage): ProcessConnections) -> Option<Vec<ConnectionUsage>> {
+    if nb_connections != 0 && usage.is_empty() {
+        return None;
+    }
+    Some(usage)
+}
+
+/// Returns the number of bytes received and sent on each of the currently opened TCP connections
+/// of the process `pid`. The connections which statistics couldn't be read are skipped.
+///
+/// Returns `None` if the process has connections but none of them could be read.
+pub(crate) fn get_tcp_connections_usage(pid: Pid) -> Option<Vec<ConnectionUsage>> {
+    let pid = pid.0 as DWORD;
+    let mut connections = unsafe { read_tcp_connections_usage(|owner| owner == pid)? };
+    into_usage(connections.remove(&pid).unwrap_or_default())
+}
+
+/// Usage of the TCP connections of several processes, read from a single snapshot of the TCP
+/// tables instead of reading them again for each process.
+pub(crate) struct TcpConnectionsUsage(Option<HashMap<DWORD, ProcessConnections>>);
+
+impl TcpConnectionsUsage {
+    /// Reads the usage of the TCP connections of the processes in `pids`.
+    pub(crate) fn new(pids: &HashSet<DWORD>) -> Self {
+        Self(unsafe { read_tcp_connections_usage(|owner| pids.contains(&owner)) })
+    }
+
+    /// Same as [`get_tcp_connections_usage`], for one of the processes of the snapshot.
+    pub(crate) fn get(&self, pid: Pid) -> Option<Vec<ConnectionUsage>> {
+        let connections = self.0.as_ref()?;
+        into_usage(
+            connections
+                .get(&(pid.0 as DWORD))
+                .cloned()
+                .unwrap_or_default(),
+        )
+    }
+}
@@ -5,6 +5,7 @@ use crate::DiskType;
 use crate::sys::disk::{new_disk, Disk};
 use crate::sys::processor::{self, Processor, Query};
 
+use once_cell::sync::Lazy;
 use std::ffi::OsStr;
 use std::mem::{size_of, zeroed};
 
@@ -35,19 +36,27 @@ impl KeyHandler {
     }
 }
 
+// Number of logical processors, which is the number of processors listed by `System`. Like
+// `GetSystemInfo`, it only counts the ones of the processor group of the current process.
+pub(crate) static NB_PROCESSORS: Lazy<usize> = Lazy::new(|| unsafe {
+    let mut sys_info: SYSTEM_INFO = zeroed();
+    GetSystemInfo(&mut sys_info);
+    sys_info.dwNumberOfProcessors as usize
+});
+
 pub(crate) fn init_processors() -> (Vec<Processor>, String, String) {
     unsafe {
         let mut sys_info: SYSTEM_INFO = zeroed();
         GetSystemInfo(&mut sys_info);
         let (vendor_id, brand) = processor::get_vendor_id_and_brand(&sys_info);
-        let frequencies = processor::get_frequencies(sys_info.dwNumberOfProcessors as usize);
-        let mut ret = Vec::with_capacity(sys_info.dwNumberOfProcessors as usize + 1);
-        for nb in 0..sys_info.dwNumberOfProcessors {
+        let frequencies = processor::get_frequencies(*NB_PROCESSORS);
+        let mut ret = Vec::with_capacity(*NB_PROCESSORS + 1);
+        for (nb, frequency) in frequencies.into_iter().enumerate() {
             ret.push(Processor::new_with_values(
                 &format!("CPU {}", nb + 1),
                 vendor_id.clone(),
                 brand.clone(),
-                frequencies[nb as usize],
+                frequency,
             ));
         }
         (ret, vendor_id, brand)
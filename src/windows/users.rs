@@ -2,17 +2,32 @@
 
 use crate::{
     common::{Gid, Uid},
-    User,
+    IntegrityLevel, User,
 };
 
+use std::mem::size_of;
+use std::ptr::null_mut;
+
 use winapi::shared::lmcons::MAX_PREFERRED_LENGTH;
+use winapi::shared::minwindef::{DWORD, FALSE};
 use winapi::shared::winerror::{ERROR_MORE_DATA, ERROR_SUCCESS};
+use winapi::um::handleapi::CloseHandle;
 use winapi::um::lmaccess::{NetQueryDisplayInformation, NetUserGetLocalGroups};
 use winapi::um::lmaccess::{
     LG_INCLUDE_INDIRECT, LPLOCALGROUP_USERS_INFO_0, PNET_DISPLAY_USER, UF_NORMAL_ACCOUNT,
 };
 use winapi::um::lmapibuf::NetApiBufferFree;
-use winapi::um::winnt::LPWSTR;
+use winapi::um::processthreadsapi::OpenProcessToken;
+use winapi::um::securitybaseapi::{
+    GetSidSubAuthority, GetSidSubAuthorityCount, GetTokenInformation,
+};
+use winapi::um::winbase::LookupAccountSidW;
+use winapi::um::winnt::{
+    TokenElevation, TokenIntegrityLevel, TokenUser, HANDLE, LPWSTR, PSID,
+    SECURITY_MANDATORY_HIGH_RID, SECURITY_MANDATORY_LOW_RID, SECURITY_MANDATORY_MEDIUM_RID,
+    SECURITY_MANDATORY_SYSTEM_RID, TOKEN_ELEVATION, TOKEN_INFORMATION_CLASS, TOKEN_MANDATORY_LABEL,
+    TOKEN_QUERY, TOKEN_USER,
+};
 
 unsafe fn to_str(p: LPWSTR) -> String {
     let mut i = 0;
@@ -113,3 +128,123 @@ pub unsafe fn get_users() -> Vec<User> {
     }
     users
 }
+
+// Calls `f` with the token of the process `process_handler`.
+unsafe fn with_process_token<T, F: FnOnce(HANDLE) -> Option<T>>(
+    process_handler: HANDLE,
+    f: F,
+) -> Option<T> {
+    let mut token = null_mut();
+    if OpenProcessToken(process_handler, TOKEN_QUERY, &mut token) == FALSE {
+        sysinfo_debug!("OpenProcessToken failed");
+        return None;
+    }
+    let ret = f(token);
+    CloseHandle(token);
+    ret
+}
+
+// Returns the variable-sized information `class` of `token`.
+unsafe fn get_token_information(
+    token: HANDLE,
+    class: TOKEN_INFORMATION_CLASS,
+) -> Option<Vec<usize>> {
+    let mut size = 0;
+    GetTokenInformation(token, class, null_mut(), 0, &mut size);
+    // The returned structures contain pointers so the buffer needs to be aligned for them.
+    let mut buffer: Vec<usize> = vec![0; size as usize / size_of::<usize>() + 1];
+    if GetTokenInformation(
+        token,
+        class,
+        buffer.as_mut_ptr() as _,
+        (buffer.len() * size_of::<usize>()) as DWORD,
+        &mut size,
+    ) == FALSE
+    {
+        sysinfo_debug!("GetTokenInformation({}) failed", class);
+        return None;
+    }
+    Some(buffer)
+}
+
+// Returns the last sub-authority of `sid`, which is its relative ID.
+unsafe fn get_sid_rid(sid: PSID) -> Option<DWORD> {
+    let nb_sub_authorities = *GetSidSubAuthorityCount(sid);
+    if nb_sub_authorities == 0 {
+        return None;
+    }
+    Some(*GetSidSubAuthority(sid, nb_sub_authorities as DWORD - 1))
+}
+
+/// Returns the ID (the relative ID of its SID, like the one of the users returned by
+/// `get_users`) and the `DOMAIN\user` name of the user of `token`.
+pub(crate) unsafe fn get_token_user(token: HANDLE) -> Option<(Uid, String)> {
+    let buffer = get_token_information(token, TokenUser)?;
+    let sid = (*(buffer.as_ptr() as *const TOKEN_USER)).User.Sid;
+    let uid = Uid(get_sid_rid(sid)?);
+
+    let mut name = [0u16; 256];
+    let mut name_len = name.len() as DWORD;
+    let mut domain = [0u16; 256];
+    let mut domain_len = domain.len() as DWORD;
+    let mut sid_type = 0;
+    if LookupAccountSidW(
+        null_mut(),
+        sid,
+        name.as_mut_ptr(),
+        &mut name_len,
+        domain.as_mut_ptr(),
+        &mut domain_len,
+        &mut sid_type,
+    ) == FALSE
+    {
+        sysinfo_debug!("LookupAccountSidW failed");
+        return None;
+    }
+    let name = String::from_utf16_lossy(&name[..name_len as usize]);
+    let domain = String::from_utf16_lossy(&domain[..domain_len as usize]);
+    Some((uid, format!("{}\\{}", domain, name)))
+}
+
+/// Same as `get_token_user`, for the user running the process `process_handler`.
+pub(crate) unsafe fn get_process_user(process_handler: HANDLE) -> Option<(Uid, String)> {
+    with_process_token(process_handler, |token| get_token_user(token))
+}
+
+pub(crate) unsafe fn is_process_elevated(process_handler: HANDLE) -> Option<bool> {
+    with_process_token(process_handler, |token| {
+        let mut elevation = TOKEN_ELEVATION { TokenIsElevated: 0 };
+        let mut size = 0;
+        if GetTokenInformation(
+            token,
+            TokenElevation,
+            &mut elevation as *mut TOKEN_ELEVATION as _,
+            size_of::<TOKEN_ELEVATION>() as DWORD,
+            &mut size,
+        ) == FALSE
+        {
+            sysinfo_debug!("GetTokenInformation(TokenElevation) failed");
+            return None;
+        }
+        Some(elevation.TokenIsElevated != 0)
+    })
+}
+
+pub(crate) unsafe fn get_process_integrity_level(
+    process_handler: HANDLE,
+) -> Option<IntegrityLevel> {
+    let buffer = with_process_token(process_handler, |token| {
+        get_token_information(token, TokenIntegrityLevel)
+    })?;
+    let sid = (*(buffer.as_ptr() as *const TOKEN_MANDATORY_LABEL))
+        .Label
+        .Sid;
+    // Levels in between the standard ones (like "medium plus") belong to the lower one.
+    Some(match get_sid_rid(sid)? {
+        rid if rid < SECURITY_MANDATORY_LOW_RID => IntegrityLevel::Untrusted,
+        rid if rid < SECURITY_MANDATORY_MEDIUM_RID => IntegrityLevel::Low,
+        rid if rid < SECURITY_MANDATORY_HIGH_RID => IntegrityLevel::Medium,
+        rid if rid < SECURITY_MANDATORY_SYSTEM_RID => IntegrityLevel::High,
+        _ => IntegrityLevel::System,
+    })
+}
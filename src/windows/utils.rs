@@ -1,6 +1,7 @@
 // Take a look at the license at the top of the repository in the LICENSE file.
 
-use winapi::shared::minwindef::FILETIME;
+use winapi::shared::minwindef::{DWORD, FILETIME, MAX_PATH};
+use winapi::um::fileapi::{GetLogicalDrives, QueryDosDeviceW};
 
 use std::time::SystemTime;
 
@@ -16,3 +17,31 @@ pub(crate) fn get_now() -> u64 {
         .map(|n| n.as_secs())
         .unwrap_or(0)
 }
+
+/// Converts a path starting with an NT device name (like `\Device\HarddiskVolume1\...`) to a
+/// path starting with the matching drive letter. The path is returned unchanged if no drive
+/// matches.
+pub(crate) fn device_path_to_dos_path(path: &str) -> String {
+    unsafe {
+        let drives = GetLogicalDrives();
+        let mut target = [0u16; MAX_PATH + 1];
+
+        for x in 0..DWORD::BITS {
+            if (drives >> x) & 1 == 0 {
+                continue;
+            }
+            let drive = [b'A' as u16 + x as u16, b':' as u16, 0];
+            let len = QueryDosDeviceW(drive.as_ptr(), target.as_mut_ptr(), target.len() as DWORD);
+            if len == 0 {
+                continue;
+            }
+            let device = String::from_utf16_lossy(&target[..libc::wcslen(target.as_ptr())]);
+            if let Some(rest) = path.strip_prefix(&device) {
+                if rest.starts_with('\\') {
+                    return format!("{}{}", String::from_utf16_lossy(&drive[..2]), rest);
+                }
+            }
+        }
+    }
+    path.to_owned()
+}
@@ -0,0 +1,107 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use crate::Pid;
+
+use winapi::ctypes::c_int;
+use winapi::shared::minwindef::{BOOL, DWORD, LPARAM, TRUE};
+use winapi::shared::windef::HWND;
+use winapi::um::wincon::{GetConsoleProcessList, GetConsoleWindow};
+use winapi::um::winuser::{
+    EnumWindows, GetClassNameW, GetForegroundWindow, GetWindowThreadProcessId, PostMessageW,
+    WM_CLOSE,
+};
+
+struct EnumData {
+    pid: DWORD,
+    windows: Vec<HWND>,
+}
+
+unsafe extern "system" fn enum_windows_proc(window: HWND, data: LPARAM) -> BOOL {
+    let data = &mut *(data as *mut EnumData);
+    let mut pid: DWORD = 0;
+    GetWindowThreadProcessId(window, &mut pid);
+    if pid == data.pid {
+        data.windows.push(window);
+    }
+    TRUE
+}
+
+/// Returns the top-level windows of the desktop of the current process which belong to the
+/// process `pid`.
+pub(crate) fn get_process_windows(pid: Pid) -> Vec<HWND> {
+    let mut data = EnumData {
+        pid: pid.0 as DWORD,
+        windows: Vec::new(),
+    };
+    unsafe {
+        EnumWindows(
+            Some(enum_windows_proc),
+            &mut data as *mut EnumData as LPARAM,
+        );
+    }
+    data.windows
+}
+
+/// Asks the top-level windows of the process `pid` to close, like when the user clicks on their
+/// close button. Returns `true` if at least one window was asked to close.
+pub(crate) fn close_process_windows(pid: Pid) -> bool {
+    get_process_windows(pid)
+        .into_iter()
+        .fold(false, |closed, window| unsafe {
+            PostMessageW(window, WM_CLOSE, 0, 0) != 0 || closed
+        })
+}
+
+/// Returns the process owning the window which currently has the focus.
+pub(crate) fn get_foreground_process() -> Option<Pid> {
+    unsafe {
+        let window = GetForegroundWindow();
+        if window.is_null() {
+            return None;
+        }
+        let mut pid: DWORD = 0;
+        if GetWindowThreadProcessId(window, &mut pid) == 0 || pid == 0 {
+            return None;
+        }
+        Some(Pid(pid as _))
+    }
+}
+
+// Class of the windows created by conhost for the console applications.
+const CONSOLE_WINDOW_CLASS: &str = "ConsoleWindowClass";
+
+unsafe fn get_class_name(window: HWND) -> String {
+    let mut buffer = [0u16; 256];
+    let len = GetClassNameW(window, buffer.as_mut_ptr(), buffer.len() as c_int);
+    String::from_utf16_lossy(&buffer[..len.max(0) as usize])
+}
+
+/// Returns `true` if the process `pid` is attached to the console of the current process.
+pub(crate) fn is_attached_to_console(pid: Pid) -> bool {
+    let mut pids: Vec<DWORD> = vec![0; 64];
+    loop {
+        let nb_pids =
+            unsafe { GetConsoleProcessList(pids.as_mut_ptr(), pids.len() as DWORD) as usize };
+        if nb_pids <= pids.len() {
+            return pids[..nb_pids].contains(&(pid.0 as DWORD));
+        }
+        // More processes are attached than the buffer can hold.
+        pids.resize(nb_pids, 0);
+    }
+}
+
+/// Returns the window of the console attached to the process `pid`.
+pub(crate) fn get_console_window(pid: Pid) -> Option<HWND> {
+    // If the process is attached to the console of the current process, the console window can
+    // be retrieved directly.
+    if is_attached_to_console(pid) {
+        let window = unsafe { GetConsoleWindow() };
+        if !window.is_null() {
+            return Some(window);
+        }
+    }
+    // Otherwise, conhost gives the console window to the process which created it.
+    get_process_windows(pid)
+        .into_iter()
+        .find(|window| unsafe { get_class_name(*window) == CONSOLE_WINDOW_CLASS })
+}
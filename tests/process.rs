@@ -1,6 +1,6 @@
 // Take a look at the license at the top of the repository in the LICENSE file.
 
-use sysinfo::{Pid, PidExt, ProcessExt, SystemExt};
+use sysinfo::{Pid, PidExt, ProcessExt, ProcessRefreshKind, SystemExt};
 
 #[test]
 fn test_process() {
@@ -109,6 +109,7 @@ fn test_environ() {
             .stdout(std::process::Stdio::null())
             .env("FOO", "BAR")
             .env("OTHER", "VALUE")
+            .env("WITH_EQUAL", "a=b")
             .spawn()
             .unwrap()
     } else {
@@ -117,6 +118,7 @@ fn test_environ() {
             .stdout(std::process::Stdio::null())
             .env("FOO", "BAR")
             .env("OTHER", "VALUE")
+            .env("WITH_EQUAL", "a=b")
             .spawn()
             .unwrap()
     };
@@ -136,6 +138,8 @@ fn test_environ() {
         if std::env::var("APPLE_CI").is_err() {
             assert!(p.environ().iter().any(|e| e == "FOO=BAR"));
             assert!(p.environ().iter().any(|e| e == "OTHER=VALUE"));
+            assert_eq!(p.environ_var("WITH_EQUAL"), Some("a=b"));
+            assert!(p.environ_vars().contains(&("FOO", "BAR")));
         }
     } else {
         panic!("Process not found!");
@@ -287,6 +291,11 @@ fn test_process_times() {
                     .as_secs(),
         );
         assert!(p.start_time() >= s.boot_time());
+        let start = p.start_time_system().expect("no start time");
+        assert_eq!(
+            start.duration_since(UNIX_EPOCH).unwrap().as_secs(),
+            p.start_time()
+        );
     } else {
         panic!("Process not found!");
     }
@@ -373,3 +382,425 @@ fn test_refresh_process() {
     // Checks that the process is still listed.
     assert!(s.process(pid).is_some());
 }
+
+#[test]
+fn test_processes_by_start_time() {
+    if !sysinfo::System::IS_SUPPORTED || cfg!(feature = "apple-sandbox") {
+        return;
+    }
+    let mut s = sysinfo::System::new();
+    s.refresh_processes();
+    let processes = s.processes_by_start_time();
+    assert_eq!(processes.len(), s.processes().len());
+    assert!(processes
+        .windows(2)
+        .all(|w| w[0].start_time() <= w[1].start_time()));
+}
+
+#[test]
+fn test_process_for_child() {
+    if !sysinfo::System::IS_SUPPORTED || cfg!(feature = "apple-sandbox") {
+        return;
+    }
+    let mut p = if cfg!(target_os = "windows") {
+        std::process::Command::new("waitfor")
+            .arg("/t")
+            .arg("300")
+            .arg("ProcessForChild")
+            .stdout(std::process::Stdio::null())
+            .spawn()
+            .unwrap()
+    } else {
+        std::process::Command::new("sleep")
+            .arg("300")
+            .stdout(std::process::Stdio::null())
+            .spawn()
+            .unwrap()
+    };
+
+    let mut s = sysinfo::System::new();
+    s.refresh_processes();
+    let process = s.process_for_child(&p).map(|process| process.pid());
+
+    p.kill().expect("Unable to kill process.");
+    let _ = p.wait();
+    assert_eq!(process, Some(Pid::from_u32(p.id())));
+}
+
+#[test]
+fn test_thread_count() {
+    if !sysinfo::System::IS_SUPPORTED || cfg!(feature = "apple-sandbox") {
+        return;
+    }
+    let pid = sysinfo::get_current_pid().expect("failed to get current pid");
+    let mut s = sysinfo::System::new();
+    s.refresh_process_specifics(pid, ProcessRefreshKind::new());
+    assert_eq!(s.process(pid).map(|p| p.thread_count()), Some(0));
+    s.refresh_process_specifics(pid, ProcessRefreshKind::new().with_threads());
+    // There is at least the thread running this test.
+    assert!(s.process(pid).map(|p| p.thread_count()).unwrap_or(0) > 0);
+}
+
+#[test]
+fn test_suspend_resume() {
+    if !sysinfo::System::IS_SUPPORTED || cfg!(feature = "apple-sandbox") {
+        return;
+    }
+    let mut p = if cfg!(target_os = "windows") {
+        std::process::Command::new("waitfor")
+            .arg("/t")
+            .arg("300")
+            .arg("SuspendResume")
+            .stdout(std::process::Stdio::null())
+            .spawn()
+            .unwrap()
+    } else {
+        std::process::Command::new("sleep")
+            .arg("300")
+            .stdout(std::process::Stdio::null())
+            .spawn()
+            .unwrap()
+    };
+    let pid = Pid::from_u32(p.id());
+    let mut s = sysinfo::System::new();
+    s.refresh_process(pid);
+    let (suspended, resumed) = match s.process(pid) {
+        Some(process) => (process.suspend(), process.resume()),
+        None => (false, false),
+    };
+
+    p.kill().expect("Unable to kill process.");
+    let _ = p.wait();
+    assert!(suspended);
+    assert!(resumed);
+}
+
+#[test]
+fn test_process_forest() {
+    fn count(node: &sysinfo::ProcessNode) -> usize {
+        1 + node.children.iter().map(count).sum::<usize>()
+    }
+
+    let mut s = sysinfo::System::new();
+    s.refresh_processes();
+    let forest = s.process_forest();
+    assert_eq!(forest.iter().map(count).sum::<usize>(), s.processes().len());
+    if !sysinfo::System::IS_SUPPORTED || cfg!(feature = "apple-sandbox") {
+        return;
+    }
+    assert!(!forest.is_empty());
+}
+
+#[test]
+fn test_parent_name() {
+    if !sysinfo::System::IS_SUPPORTED || cfg!(feature = "apple-sandbox") {
+        return;
+    }
+    let pid = sysinfo::get_current_pid().expect("failed to get current pid");
+    let mut s = sysinfo::System::new();
+    s.refresh_processes();
+    let p = s.process(pid).expect("current process not found");
+    let parent = p.parent().and_then(|parent| s.process(parent));
+    assert_eq!(
+        s.parent_name(pid),
+        parent.map(|parent| parent.name().to_owned())
+    );
+    assert_eq!(s.parent_checked(pid), parent.map(|parent| parent.pid()));
+}
+
+#[test]
+fn test_process_children() {
+    if !sysinfo::System::IS_SUPPORTED || cfg!(feature = "apple-sandbox") {
+        return;
+    }
+    let mut p = if cfg!(target_os = "windows") {
+        std::process::Command::new("waitfor")
+            .arg("/t")
+            .arg("3")
+            .arg("ChildrenSignal")
+            .stdout(std::process::Stdio::null())
+            .spawn()
+            .unwrap()
+    } else {
+        std::process::Command::new("sleep")
+            .arg("3")
+            .stdout(std::process::Stdio::null())
+            .spawn()
+            .unwrap()
+    };
+    let pid = sysinfo::get_current_pid().expect("failed to get current pid");
+    let child = Pid::from_u32(p.id());
+    let mut s = sysinfo::System::new();
+    s.refresh_processes();
+    p.kill().expect("Unable to kill process.");
+    assert!(s.process_children(pid).iter().any(|p| p.pid() == child));
+    assert!(s.process_descendants(pid).iter().any(|p| p.pid() == child));
+    assert!(s.process_descendants(pid).iter().all(|p| p.pid() != pid));
+}
+
+#[test]
+fn test_kill_process_tree() {
+    if !sysinfo::System::IS_SUPPORTED || cfg!(feature = "apple-sandbox") {
+        return;
+    }
+    let mut p = if cfg!(target_os = "windows") {
+        std::process::Command::new("cmd")
+            .arg("/c")
+            .arg("waitfor /t 30 KillTreeSignal")
+            .stdout(std::process::Stdio::null())
+            .spawn()
+            .unwrap()
+    } else {
+        std::process::Command::new("sh")
+            .arg("-c")
+            .arg("sleep 30 & sleep 30")
+            .stdout(std::process::Stdio::null())
+            .spawn()
+            .unwrap()
+    };
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    let pid = Pid::from_u32(p.id());
+    let mut s = sysinfo::System::new();
+    s.refresh_processes();
+    assert!(!s.process_descendants(pid).is_empty());
+    assert!(s.kill_process_tree(pid, sysinfo::Signal::Kill));
+    p.wait().expect("Unable to wait for the process");
+}
+
+#[test]
+#[cfg(any(target_os = "linux", windows))]
+fn test_cwd_refresh() {
+    if !sysinfo::System::IS_SUPPORTED || cfg!(feature = "apple-sandbox") {
+        return;
+    }
+    // The shell changes its directory to the root one after a second.
+    let mut p = if cfg!(target_os = "windows") {
+        std::process::Command::new("cmd")
+            .arg("/c")
+            .arg("ping -n 2 127.0.0.1 >nul & cd \\ & ping -n 4 127.0.0.1 >nul")
+            .current_dir(std::env::temp_dir())
+            .stdout(std::process::Stdio::null())
+            .spawn()
+            .unwrap()
+    } else {
+        std::process::Command::new("sh")
+            .arg("-c")
+            .arg("sleep 1; cd /; sleep 3")
+            .current_dir(std::env::temp_dir())
+            .stdout(std::process::Stdio::null())
+            .spawn()
+            .unwrap()
+    };
+    let pid = Pid::from_u32(p.id());
+    std::thread::sleep(std::time::Duration::from_millis(300));
+    let mut s = sysinfo::System::new();
+    s.refresh_process(pid);
+    let is_root = |s: &sysinfo::System| {
+        let cwd = s.process(pid).expect("process not found").cwd();
+        cwd.parent().is_none()
+    };
+    assert!(!is_root(&s));
+    std::thread::sleep(std::time::Duration::from_millis(1500));
+    // The current working directory isn't read again by default...
+    s.refresh_process(pid);
+    assert!(!is_root(&s));
+    // ... unless it's asked for.
+    s.refresh_process_specifics(pid, ProcessRefreshKind::new().with_cwd());
+    let changed = is_root(&s);
+    p.kill().expect("Unable to kill process.");
+    assert!(changed);
+}
+
+#[test]
+fn test_cmd_kept_across_refreshes() {
+    if !sysinfo::System::IS_SUPPORTED || cfg!(feature = "apple-sandbox") {
+        return;
+    }
+    let pid = sysinfo::get_current_pid().expect("failed to get current pid");
+    let mut s = sysinfo::System::new();
+    s.refresh_process(pid);
+    let cmd = s
+        .process(pid)
+        .expect("current process not found")
+        .cmd()
+        .to_vec();
+    assert!(!cmd.is_empty());
+    s.refresh_process_specifics(pid, ProcessRefreshKind::new());
+    assert_eq!(s.process(pid).unwrap().cmd(), cmd);
+    s.refresh_process_specifics(pid, ProcessRefreshKind::new().with_cmd());
+    assert_eq!(s.process(pid).unwrap().cmd(), cmd);
+}
+
+#[test]
+fn test_memory_bytes() {
+    if !sysinfo::System::IS_SUPPORTED || cfg!(feature = "apple-sandbox") {
+        return;
+    }
+    let pid = sysinfo::get_current_pid().expect("failed to get current pid");
+    let mut s = sysinfo::System::new();
+    s.refresh_process(pid);
+    let p = s.process(pid).expect("current process not found");
+    assert!(p.memory_bytes() > 0);
+    assert!(p.memory_bytes() >= p.memory() * 1_000);
+}
+
+#[test]
+fn test_page_faults() {
+    if !sysinfo::System::IS_SUPPORTED || cfg!(feature = "apple-sandbox") {
+        return;
+    }
+    let pid = sysinfo::get_current_pid().expect("failed to get current pid");
+    let mut s = sysinfo::System::new();
+    s.refresh_process(pid);
+    s.refresh_process(pid);
+    let p = s.process(pid).expect("current process not found");
+    assert!(p.page_faults() > 0);
+    assert!(p.page_faults_delta() <= p.page_faults());
+}
+
+#[test]
+fn test_total_memory_by_exe() {
+    if !sysinfo::System::IS_SUPPORTED || cfg!(feature = "apple-sandbox") {
+        return;
+    }
+    let pid = sysinfo::get_current_pid().expect("failed to get current pid");
+    let mut s = sysinfo::System::new();
+    s.refresh_processes();
+    let p = s.process(pid).expect("current process not found");
+    assert!(s.total_memory_by_exe(p.exe()) >= p.memory());
+    assert!(s.total_memory_by_name(p.name()) >= p.memory());
+}
+
+#[test]
+#[cfg(windows)]
+fn test_process_handles_are_closed() {
+    let pid = sysinfo::get_current_pid().expect("failed to get current pid");
+    let handle_count = || {
+        let mut s = sysinfo::System::new();
+        s.refresh_process_specifics(pid, ProcessRefreshKind::new().with_handles());
+        s.process(pid)
+            .expect("current process not found")
+            .handle_count()
+    };
+    let before = handle_count();
+    for _ in 0..500 {
+        let mut s = sysinfo::System::new();
+        s.refresh_process(pid);
+    }
+    // Other tests running at the same time can open handles too, hence the margin.
+    assert!(handle_count() < before + 100);
+}
+
+#[test]
+#[cfg(windows)]
+fn test_session_id() {
+    let pid = sysinfo::get_current_pid().expect("failed to get current pid");
+    let mut s = sysinfo::System::new();
+    s.refresh_process_specifics(pid, ProcessRefreshKind::new().with_session());
+    let session_id = s
+        .process(pid)
+        .expect("current process not found")
+        .session_id();
+    assert!(session_id.is_some());
+    s.refresh_processes_specifics(ProcessRefreshKind::new().with_session());
+    assert_eq!(s.process(pid).unwrap().session_id(), session_id);
+}
+
+#[test]
+#[cfg(windows)]
+fn test_accumulated_cpu_time() {
+    let pid = sysinfo::get_current_pid().expect("failed to get current pid");
+    let mut s = sysinfo::System::new();
+    s.refresh_process(pid);
+    let p = s.process(pid).expect("current process not found");
+    assert!(p.accumulated_cpu_time() > std::time::Duration::from_secs(0));
+    assert_eq!(p.accumulated_cpu_time(), p.kernel_time() + p.user_time());
+}
+
+#[test]
+#[cfg(windows)]
+fn test_cpu_usage_machine_share() {
+    let pid = sysinfo::get_current_pid().expect("failed to get current pid");
+    let mut s = sysinfo::System::new();
+    s.refresh_cpu();
+    s.refresh_process(pid);
+    let start = std::time::Instant::now();
+    while start.elapsed() < std::time::Duration::from_millis(300) {}
+    s.refresh_process(pid);
+    let p = s.process(pid).expect("current process not found");
+    let nb_processors = s.processors().len() as f32;
+    assert!(p.cpu_usage_machine_share() > 0.);
+    assert!(p.cpu_usage_machine_share() <= 100.);
+    assert!((p.cpu_usage_machine_share() * nb_processors - p.cpu_usage()).abs() < 0.01);
+}
+
+#[test]
+#[cfg(windows)]
+fn test_network_usage() {
+    let pid = sysinfo::get_current_pid().expect("failed to get current pid");
+    let mut s = sysinfo::System::new();
+    s.refresh_process_specifics(pid, ProcessRefreshKind::new());
+    assert_eq!(s.process(pid).unwrap().network_usage(), None);
+    s.refresh_process_specifics(pid, ProcessRefreshKind::new().with_network());
+    // The statistics collection isn't enabled, so nothing is counted.
+    let usage = s.process(pid).unwrap().network_usage();
+    assert_eq!(usage, Some(sysinfo::NetworkUsage::default()));
+}
+
+#[test]
+#[cfg(windows)]
+fn test_exit_code() {
+    let mut p = std::process::Command::new("waitfor")
+        .arg("/t")
+        .arg("3")
+        .arg("ExitCodeSignal")
+        .stdout(std::process::Stdio::null())
+        .spawn()
+        .unwrap();
+    let pid = Pid::from_u32(p.id());
+    let mut s = sysinfo::System::new();
+    s.refresh_process(pid);
+    let process = s.process(pid).expect("process not found");
+    assert_eq!(process.exit_code(), None);
+    // `Child::kill` terminates the process with the exit code `1`.
+    p.kill().expect("Unable to kill process.");
+    p.wait().expect("Unable to wait for the process");
+    assert_eq!(process.exit_code(), Some(1));
+}
+
+#[test]
+#[cfg(windows)]
+fn test_wait_timeout() {
+    let mut p = std::process::Command::new("waitfor")
+        .arg("/t")
+        .arg("3")
+        .arg("WaitTimeoutSignal")
+        .stdout(std::process::Stdio::null())
+        .spawn()
+        .unwrap();
+    let pid = Pid::from_u32(p.id());
+    let mut s = sysinfo::System::new();
+    s.refresh_process(pid);
+    let process = s.process(pid).expect("process not found");
+    assert_eq!(
+        process.wait_timeout(std::time::Duration::from_millis(10)),
+        None
+    );
+    p.kill().expect("Unable to kill process.");
+    assert_eq!(process.wait(), Some(1));
+    // The process can still be used once it exited.
+    assert_eq!(process.exit_code(), Some(1));
+}
+
+#[test]
+#[cfg(windows)]
+fn test_modules() {
+    let pid = sysinfo::get_current_pid().expect("failed to get current pid");
+    let mut s = sysinfo::System::new();
+    s.refresh_process_specifics(pid, ProcessRefreshKind::new().with_modules());
+    let p = s.process(pid).expect("current process not found");
+    let modules = p.modules();
+    assert!(modules.len() > 1);
+    assert_eq!(modules[0].path, std::env::current_exe().unwrap());
+    assert!(modules.iter().all(|module| module.size > 0));
+}